@@ -18,4 +18,12 @@ fn main() {
     }
     loading.fail("Error ...");
     loading.end();
+
+    let loading = Loading::with_stdout(Spinner::bounce(12));
+    for i in 0..10 {
+        loading.text(format!("Loading {}", i));
+        thread::sleep(Duration::from_millis(200));
+    }
+    loading.success("Successs ...");
+    loading.end();
 }
@@ -0,0 +1,52 @@
+//! Route the events of a loading through channels you own
+//!
+//! This module is unstable: [`Event`] is opaque and its contents may change in any release.
+//!
+//! ```
+//! use loading::advanced::{self, Event};
+//! use loading::Loading;
+//! use std::sync::mpsc;
+//!
+//! let (sender, receiver) = mpsc::channel::<Event>();
+//! let handle = advanced::render(receiver, Loading::default());
+//!
+//! let loading = advanced::loading(sender);
+//! loading.success("Sent through our own channel");
+//! loading.end();
+//! handle.join().unwrap();
+//! ```
+
+use crate::{Loading, Signal};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+/// An update of a loading, e.g. a new text or a status line
+#[derive(Debug)]
+pub struct Event(Signal);
+
+/// Create a loading without animation that sends its events to `sender` instead of rendering them
+pub fn loading(sender: Sender<Event>) -> Loading {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        while let Ok(signal) = rx.recv() {
+            if sender.send(Event(signal)).is_err() {
+                break;
+            }
+        }
+    });
+    Loading::from_sender(tx)
+}
+
+/// Render the events received from `receiver` with `loading`,
+/// which ends when the sending loading ends or all senders are dropped
+pub fn render(receiver: Receiver<Event>, loading: Loading) -> JoinHandle<()> {
+    thread::spawn(move || {
+        while let Ok(Event(signal)) = receiver.recv() {
+            let exit = matches!(signal, Signal::Exit(_));
+            if loading.sender.send(signal).is_err() || exit {
+                return;
+            }
+        }
+        loading.end();
+    })
+}
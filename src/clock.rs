@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+/// Elapsed time that leaves out the paused intervals, unless it is set to wall clock time
+#[derive(Debug)]
+pub(crate) struct Clock {
+    start: Instant,
+    paused_at: Option<Instant>,
+    paused: Duration,
+    wall: bool,
+}
+
+impl Clock {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            paused_at: None,
+            paused: Duration::ZERO,
+            wall: false,
+        }
+    }
+
+    // Start counting from zero again, keeping the wall clock setting
+    pub(crate) fn reset(&mut self) {
+        *self = Self {
+            wall: self.wall,
+            ..Self::new()
+        };
+    }
+
+    pub(crate) fn pause(&mut self) {
+        self.paused_at.get_or_insert_with(Instant::now);
+    }
+
+    pub(crate) fn resume(&mut self) {
+        if let Some(at) = self.paused_at.take() {
+            self.paused += at.elapsed();
+        }
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    pub(crate) fn wall_clock(&mut self, enable: bool) {
+        self.wall = enable;
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        let elapsed = self.start.elapsed();
+        if self.wall {
+            return elapsed;
+        }
+        let paused = self.paused + self.paused_at.map_or(Duration::ZERO, |at| at.elapsed());
+        elapsed.saturating_sub(paused)
+    }
+}
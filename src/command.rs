@@ -0,0 +1,157 @@
+use crate::Loading;
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Result, Write};
+use std::process::{self, Command, ExitStatus};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Lines of output printed under the fail line, the rest is only in the log file
+const DETAIL_LINES: usize = 20;
+
+// The number of the next log file
+static LOGS: AtomicUsize = AtomicUsize::new(0);
+
+impl Loading {
+    /// Run a command and save the line as 'success' or 'fail' depending on its exit status
+    ///
+    /// The output of the command is hidden, and printed under the fail line if it fails.
+    /// Long output is truncated and written to a log file in the temporary directory
+    ///
+    /// ```no_run
+    /// use loading::Loading;
+    /// use std::process::Command;
+    ///
+    /// let loading = Loading::default();
+    /// loading.command("Build", Command::new("cargo").arg("build")).unwrap();
+    /// loading.end();
+    /// ```
+    pub fn command<T: ToString>(&self, text: T, command: &mut Command) -> Result<ExitStatus> {
+        let text = text.to_string();
+        self.text(&text);
+
+        let output = command.output()?;
+        if output.status.success() {
+            self.success(text);
+            return Ok(output.status);
+        }
+
+        let log = combine(&output.stdout, &output.stderr);
+        self.detail(detail(&log, save_log));
+        self.fail(format!("{} ({})", text, output.status));
+        Ok(output.status)
+    }
+}
+
+// The output of a command, stderr after stdout on its own line
+fn combine(stdout: &[u8], stderr: &[u8]) -> String {
+    let mut log = String::from_utf8_lossy(stdout).into_owned();
+    if !log.is_empty() && !log.ends_with('\n') && !stderr.is_empty() {
+        log.push('\n');
+    }
+    log.push_str(&String::from_utf8_lossy(stderr));
+    log
+}
+
+// The first lines of the output, and where to find the rest when it is too long
+fn detail<F: FnOnce(&str) -> Option<String>>(log: &str, save: F) -> String {
+    let lines = log.lines().collect::<Vec<_>>();
+    let mut detail = lines[..lines.len().min(DETAIL_LINES)].join("\n");
+    if lines.len() > DETAIL_LINES {
+        let hidden = lines.len() - DETAIL_LINES;
+        match save(log) {
+            Some(path) => detail.push_str(&format!("\n… {} more lines, see {}", hidden, path)),
+            None => detail.push_str(&format!("\n… {} more lines", hidden)),
+        }
+    }
+    detail
+}
+
+// Write the full output of a failed command to a new file, readable only by the user
+fn save_log(log: &str) -> Option<String> {
+    let mut options = OpenOptions::new();
+    // Fails if the file exists, so a planted file or symlink is never written through
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    for _ in 0..100 {
+        let path = env::temp_dir().join(format!(
+            "loading-{}-{}.log",
+            process::id(),
+            LOGS.fetch_add(1, Ordering::Relaxed)
+        ));
+        match options.open(&path) {
+            Ok(mut file) => {
+                file.write_all(log.as_bytes()).ok()?;
+                return Some(path.display().to_string());
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combine_output() {
+        assert_eq!(combine(b"out", b"err"), "out\nerr");
+        assert_eq!(combine(b"out\n", b"err"), "out\nerr");
+        assert_eq!(combine(b"", b"err"), "err");
+        assert_eq!(combine(b"out", b""), "out");
+    }
+
+    #[test]
+    fn short_detail() {
+        let detail = detail("a\nb\n", |_| panic!("saved a short log"));
+        assert_eq!(detail, "a\nb");
+    }
+
+    #[test]
+    fn truncated_detail() {
+        let log = (1..=25).map(|i| format!("{}\n", i)).collect::<String>();
+        let mut saved = String::new();
+        let truncated = detail(&log, |log| {
+            saved = log.to_string();
+            Some(String::from("/tmp/out.log"))
+        });
+        assert_eq!(saved, log);
+        let lines = truncated.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), DETAIL_LINES + 1);
+        assert_eq!(lines[DETAIL_LINES - 1], "20");
+        assert_eq!(lines[DETAIL_LINES], "… 5 more lines, see /tmp/out.log");
+
+        let unsaved = detail(&log, |_| None);
+        assert!(unsaved.ends_with("\n… 5 more lines"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_log() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        // Another user prepared the next name as a symlink to one of our files
+        let target = env::temp_dir().join(format!("loading-target-{}", process::id()));
+        fs::write(&target, "keep").unwrap();
+        let next = env::temp_dir().join(format!(
+            "loading-{}-{}.log",
+            process::id(),
+            LOGS.load(Ordering::Relaxed)
+        ));
+        std::os::unix::fs::symlink(&target, &next).unwrap();
+
+        let path = save_log("output").unwrap();
+        assert_ne!(path, next.display().to_string());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "keep");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "output");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        for file in [&target, &next, &path.into()] {
+            let _ = fs::remove_file(file);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! A facade with the method names of `indicatif::ProgressBar`, to ease migrating from indicatif
+//!
+//! Only the text based parts are mapped: the position is shown as `pos/len` after the message,
+//! and `println` prints an 'info' line. Like indicatif it draws on stderr, and its clones
+//! share the same line, so it can be handed to worker threads.
+//!
+//! ```
+//! use loading::compat::ProgressBar;
+//!
+//! let pb = ProgressBar::new(3);
+//! for _ in 0..3 {
+//!     pb.inc(1);
+//! }
+//! pb.finish_with_message("done");
+//! ```
+
+use crate::{Loading, Spinner};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct State {
+    message: String,
+    pos: u64,
+    len: Option<u64>,
+}
+
+/// A [`Loading`] with the method names of `indicatif::ProgressBar`
+///
+/// It ends when the last clone is dropped
+#[derive(Debug, Clone)]
+pub struct ProgressBar {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    loading: Mutex<Option<Loading>>,
+    state: Mutex<State>,
+}
+
+impl ProgressBar {
+    /// Create a progress bar with a length
+    pub fn new(len: u64) -> Self {
+        let pb = Self::new_spinner();
+        pb.set_length(len);
+        pb
+    }
+
+    /// Create a spinner without a length
+    pub fn new_spinner() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                loading: Mutex::new(Some(Loading::with_stderr(Spinner::default()))),
+                state: Mutex::new(State::default()),
+            }),
+        }
+    }
+
+    /// Change the message
+    pub fn set_message<T: Into<String>>(&self, msg: T) {
+        self.update(|state| state.message = msg.into());
+    }
+
+    /// Advance the position by `delta`
+    pub fn inc(&self, delta: u64) {
+        self.update(|state| state.pos += delta);
+    }
+
+    /// Change the position
+    pub fn set_position(&self, pos: u64) {
+        self.update(|state| state.pos = pos);
+    }
+
+    /// Change the length
+    pub fn set_length(&self, len: u64) {
+        self.update(|state| state.len = Some(len));
+    }
+
+    /// Show the next frame when it is due and write the pending updates,
+    /// only needed with the `no-threads` feature, see [`Loading::tick`]
+    pub fn tick(&self) {
+        #[cfg(feature = "no-threads")]
+        self.with_loading(|loading| loading.tick());
+    }
+
+    /// The current position
+    pub fn position(&self) -> u64 {
+        self.lock_state().pos
+    }
+
+    /// Print a line above the progress bar
+    pub fn println<T: AsRef<str>>(&self, msg: T) {
+        self.with_loading(|loading| loading.info(msg.as_ref()));
+    }
+
+    /// End and keep the message as a 'success' line
+    pub fn finish(&self) {
+        let message = self.lock_state().message.clone();
+        self.finish_with_message(message);
+    }
+
+    /// End and keep `msg` as a 'success' line
+    pub fn finish_with_message<T: Into<String>>(&self, msg: T) {
+        let msg = msg.into();
+        self.end(|loading| loading.success(msg));
+    }
+
+    /// End and clear the line
+    pub fn finish_and_clear(&self) {
+        self.end(|_| {});
+    }
+
+    /// End and keep the message as a 'fail' line
+    pub fn abandon(&self) {
+        let message = self.lock_state().message.clone();
+        self.abandon_with_message(message);
+    }
+
+    /// End and keep `msg` as a 'fail' line
+    pub fn abandon_with_message<T: Into<String>>(&self, msg: T) {
+        let msg = msg.into();
+        self.end(|loading| loading.fail(msg));
+    }
+
+    /// Whether the progress bar has ended
+    pub fn is_finished(&self) -> bool {
+        self.inner
+            .loading
+            .lock()
+            .map_or(true, |loading| loading.is_none())
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update<F: FnOnce(&mut State)>(&self, f: F) {
+        let (text, percent) = {
+            let mut state = self.lock_state();
+            f(&mut state);
+            match state.len {
+                Some(len) => (
+                    format!("{} {}/{}", state.message, state.pos, len),
+                    (len > 0).then(|| state.pos * 100 / len),
+                ),
+                None => (state.message.clone(), None),
+            }
+        };
+        self.with_loading(|loading| {
+            loading.text(text.trim_start());
+            if let Some(percent) = percent {
+                loading.percent(percent);
+            }
+            #[cfg(feature = "no-threads")]
+            loading.tick();
+        });
+    }
+
+    fn with_loading<F: FnOnce(&Loading)>(&self, f: F) {
+        if let Ok(loading) = self.inner.loading.lock() {
+            if let Some(loading) = loading.as_ref() {
+                f(loading);
+            }
+        }
+    }
+
+    fn end<F: FnOnce(&Loading)>(&self, f: F) {
+        self.inner.end(f);
+    }
+}
+
+impl Inner {
+    fn end<F: FnOnce(&Loading)>(&self, f: F) {
+        let loading = match self.loading.lock() {
+            Ok(mut loading) => loading.take(),
+            Err(_) => None,
+        };
+        if let Some(loading) = loading {
+            f(&loading);
+            loading.end();
+        }
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.end(|_| {});
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn shared_by_clones() {
+        let pb = ProgressBar::new(8);
+        let workers = (0..4)
+            .map(|_| {
+                let pb = pb.clone();
+                thread::spawn(move || {
+                    pb.inc(1);
+                    pb.inc(1);
+                })
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(pb.position(), 8);
+        // Dropping a clone doesn't end the others
+        drop(pb.clone());
+        assert!(!pb.is_finished());
+        pb.finish_and_clear();
+        assert!(pb.is_finished());
+    }
+}
@@ -0,0 +1,85 @@
+use crate::locale::{English, Localizer};
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+/// Format a duration for humans
+///
+/// ```
+/// use loading::HumanDuration;
+/// use std::time::Duration;
+///
+/// let d = Duration::from_secs(133);
+/// assert_eq!(HumanDuration::new(d).to_string(), "2m 13s");
+/// assert_eq!(HumanDuration::verbose(d).to_string(), "2 minutes");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HumanDuration {
+    duration: Duration,
+    verbose: bool,
+}
+
+impl HumanDuration {
+    /// Compact format, e.g. `12.4s`, `2m 13s`, `1h 5m`
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            verbose: false,
+        }
+    }
+
+    /// Verbose format, e.g. `12 seconds`, `about 1 hour`
+    pub fn verbose(duration: Duration) -> Self {
+        Self {
+            duration,
+            verbose: true,
+        }
+    }
+
+    fn fmt_compact(&self, f: &mut Formatter) -> fmt::Result {
+        let secs = self.duration.as_secs();
+        match secs {
+            0..=59 => write!(f, "{:.1}s", self.duration.as_secs_f64()),
+            60..=3599 => write!(f, "{}m {}s", secs / 60, secs % 60),
+            _ => write!(f, "{}h {}m", secs / 3600, secs % 3600 / 60),
+        }
+    }
+
+    /// Format with the words of a [`Localizer`], the compact format is the same in every language
+    ///
+    /// ```
+    /// use loading::{HumanDuration, Localizer};
+    /// use std::time::Duration;
+    ///
+    /// struct German;
+    ///
+    /// impl Localizer for German {
+    ///     fn duration(&self, duration: Duration) -> String {
+    ///         format!("{} Sekunden", duration.as_secs())
+    ///     }
+    /// }
+    ///
+    /// let d = HumanDuration::verbose(Duration::from_secs(12));
+    /// assert_eq!(d.localize(&German), "12 Sekunden");
+    /// ```
+    pub fn localize(&self, localizer: &dyn Localizer) -> String {
+        if self.verbose {
+            localizer.duration(self.duration)
+        } else {
+            self.to_string()
+        }
+    }
+
+    fn fmt_verbose(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&English.duration(self.duration))
+    }
+}
+
+impl Display for HumanDuration {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.verbose {
+            self.fmt_verbose(f)
+        } else {
+            self.fmt_compact(f)
+        }
+    }
+}
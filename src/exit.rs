@@ -0,0 +1,77 @@
+use crate::{Loading, Signal};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, Once, Weak};
+use std::time::Duration;
+
+// The loadings to clear when the process exits, with a handle that is dead once
+// the loading is ended or dropped
+static LIVE: Mutex<Vec<(Sender<Signal>, Weak<()>)>> = Mutex::new(Vec::new());
+
+// How long the exit hook waits for each loading
+const EXIT_TIMEOUT: Duration = Duration::from_millis(200);
+
+extern "C" {
+    fn atexit(f: extern "C" fn()) -> i32;
+}
+
+impl Loading {
+    /// Clear the line when the process exits through `std::process::exit`,
+    /// which skips the rest of the program, including [`Loading::end`]
+    ///
+    /// ```
+    /// use loading::Loading;
+    ///
+    /// let loading = Loading::default();
+    /// loading.clear_on_exit();
+    /// loading.text("Loading...");
+    /// loading.end();
+    /// ```
+    pub fn clear_on_exit(&self) {
+        static HOOK: Once = Once::new();
+        // SAFETY: `on_exit` is a plain function that doesn't unwind
+        HOOK.call_once(|| unsafe {
+            atexit(on_exit);
+        });
+        if let Ok(mut live) = LIVE.lock() {
+            live.retain(|(_, alive)| alive.strong_count() > 0);
+            live.push((self.sender.clone(), Arc::downgrade(&self.alive)));
+        }
+    }
+}
+
+extern "C" fn on_exit() {
+    let live = match LIVE.lock() {
+        Ok(mut live) => std::mem::take(&mut *live),
+        Err(_) => return,
+    };
+    for (loading, _) in live
+        .into_iter()
+        .filter(|(_, alive)| alive.strong_count() > 0)
+    {
+        let (sender, receiver) = mpsc::channel();
+        if loading.send(Signal::Exit(sender)).is_ok() {
+            let _ = receiver.recv_timeout(EXIT_TIMEOUT);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Spinner;
+
+    #[test]
+    fn prune_ended() {
+        for _ in 0..3 {
+            let loading = Loading::with_writer(std::io::sink(), Spinner::default());
+            loading.clear_on_exit();
+            loading.end();
+        }
+        let loading = Loading::with_writer(std::io::sink(), Spinner::default());
+        loading.clear_on_exit();
+        let live = LIVE.lock().unwrap();
+        assert_eq!(live.len(), 1);
+        drop(live);
+        loading.end();
+    }
+}
@@ -0,0 +1,86 @@
+//! C functions to drive a loading from other languages
+//!
+//! Build a C library with `cargo rustc --release --features ffi --crate-type cdylib`
+//! and declare the functions:
+//!
+//! ```c
+//! typedef struct Loading Loading;
+//!
+//! Loading *loading_new(void);
+//! void loading_text(Loading *loading, const char *text);
+//! void loading_success(Loading *loading, const char *text);
+//! void loading_end(Loading *loading);
+//! ```
+//!
+//! With the `no-threads` feature, also declare `void loading_tick(Loading *loading);`
+//! and call it regularly, e.g. from an event loop
+
+use crate::Loading;
+use std::ffi::{c_char, CStr};
+
+/// Create a stdout loading, it must be ended with [`loading_end`]
+#[no_mangle]
+pub extern "C" fn loading_new() -> *mut Loading {
+    Box::into_raw(Box::default())
+}
+
+/// Modify the currently displayed text
+///
+/// # Safety
+///
+/// `loading` must come from [`loading_new`] and not be ended,
+/// `text` must be a valid nul-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn loading_text(loading: *mut Loading, text: *const c_char) {
+    if let (Some(loading), Some(text)) = (loading.as_ref(), to_str(text)) {
+        loading.text(text);
+        #[cfg(feature = "no-threads")]
+        loading.tick();
+    }
+}
+
+/// Save the current line as 'success' and continue to load on the next line
+///
+/// # Safety
+///
+/// Same as [`loading_text`]
+#[no_mangle]
+pub unsafe extern "C" fn loading_success(loading: *mut Loading, text: *const c_char) {
+    if let (Some(loading), Some(text)) = (loading.as_ref(), to_str(text)) {
+        loading.success(text);
+        #[cfg(feature = "no-threads")]
+        loading.tick();
+    }
+}
+
+/// Show the next frame when it is due and write the pending updates, see [`Loading::tick`]
+///
+/// # Safety
+///
+/// `loading` must come from [`loading_new`] and not be ended
+#[cfg(feature = "no-threads")]
+#[no_mangle]
+pub unsafe extern "C" fn loading_tick(loading: *mut Loading) {
+    if let Some(loading) = loading.as_ref() {
+        loading.tick();
+    }
+}
+
+/// End the loading and free it
+///
+/// # Safety
+///
+/// `loading` must come from [`loading_new`] and not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn loading_end(loading: *mut Loading) {
+    if !loading.is_null() {
+        Box::from_raw(loading).end();
+    }
+}
+
+unsafe fn to_str<'a>(text: *const c_char) -> Option<std::borrow::Cow<'a, str>> {
+    if text.is_null() {
+        return None;
+    }
+    Some(CStr::from_ptr(text).to_string_lossy())
+}
@@ -0,0 +1,90 @@
+//! Render the progress of child processes in the parent process
+//!
+//! When the [`ENV`] environment variable is set to the path of a socket,
+//! a [`Loading`] created in that process sends its events to the socket instead of the terminal.
+//! The parent listens with [`Loading::listen_children`] and renders the events as nested lines.
+//!
+//! ```no_run
+//! use loading::Loading;
+//! use std::process::Command;
+//!
+//! let loading = Loading::default();
+//! let server = loading.listen_children().unwrap();
+//!
+//! Command::new("cargo")
+//!     .arg("run")
+//!     .env(loading::ipc::ENV, server.path())
+//!     .status()
+//!     .unwrap();
+//!
+//! loading.success("Done");
+//! loading.end();
+//! ```
+
+use crate::server::Server;
+use crate::Loading;
+use std::env;
+use std::fs::{self, DirBuilder};
+use std::io::Result;
+use std::os::unix::fs::DirBuilderExt;
+use std::os::unix::net::UnixStream;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The environment variable holding the socket path
+pub const ENV: &str = "LOADING_IPC";
+
+impl Loading {
+    /// Listen for the events of child processes, see [`ipc`](crate::ipc)
+    ///
+    /// The socket is created in a new directory only the current user can access
+    pub fn listen_children(&self) -> Result<Server> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let dir = env::temp_dir().join(format!(
+            "loading-{}-{}",
+            process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        // Fails if the directory exists, so it can't be prepared by another user
+        DirBuilder::new().mode(0o700).create(&dir)?;
+        match Server::bind(dir.join("events.sock"), self) {
+            Ok(mut server) => {
+                server.dir = Some(dir);
+                Ok(server)
+            }
+            Err(e) => {
+                let _ = fs::remove_dir(&dir);
+                Err(e)
+            }
+        }
+    }
+}
+
+// Connect to the parent process if `LOADING_IPC` is set
+pub(crate) fn connect() -> Option<UnixStream> {
+    let path = env::var_os(ENV)?;
+    UnixStream::connect(path).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Loading, Spinner};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::UnixStream;
+
+    #[test]
+    fn private_socket_dir() {
+        let loading = Loading::with_writer(std::io::sink(), Spinner::default());
+        let server = loading.listen_children().unwrap();
+        let path = server.path().to_path_buf();
+        let dir = path.parent().unwrap().to_path_buf();
+        let mode = fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        drop(server);
+        assert!(!dir.exists());
+        assert!(UnixStream::connect(&path).is_err());
+        loading.end();
+    }
+}
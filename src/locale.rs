@@ -0,0 +1,101 @@
+use crate::{HumanDuration, Status};
+use std::fmt::{self, Debug, Formatter};
+use std::time::Duration;
+
+/// Translates the text that the crate writes by itself, e.g. the section summaries
+/// and the verbose [`HumanDuration`]
+///
+/// Every method has an English default, override the ones to translate:
+///
+/// ```
+/// use loading::{Loading, Localizer, Status};
+///
+/// struct French;
+///
+/// impl Localizer for French {
+///     fn count(&self, status: Status, n: usize) -> String {
+///         match status {
+///             Status::Success => format!("{} réussis", n),
+///             Status::Fail => format!("{} échoués", n),
+///             Status::Warn => format!("{} avertissements", n),
+///             Status::Info => format!("{} infos", n),
+///         }
+///     }
+/// }
+///
+/// let loading = Loading::default();
+/// loading.localizer(French);
+/// loading.section("Compilation");
+/// loading.success("Parser");
+/// loading.end();
+/// ```
+pub trait Localizer: Send {
+    /// The number of lines with a status in a section summary, e.g. `3 succeeded`
+    fn count(&self, status: Status, n: usize) -> String {
+        let label = match status {
+            Status::Success => "succeeded",
+            Status::Fail => "failed",
+            Status::Warn => "warnings",
+            Status::Info => "info",
+        };
+        format!("{} {}", n, label)
+    }
+
+    /// The section summary without any status line
+    fn nothing_done(&self) -> String {
+        String::from("nothing done")
+    }
+
+    /// The label of the lines with a status in the recap, e.g. `Failed: a, b`
+    fn recap(&self, status: Status) -> String {
+        String::from(match status {
+            Status::Fail => "Failed",
+            Status::Warn => "Warnings",
+            Status::Success => "Succeeded",
+            Status::Info => "Info",
+        })
+    }
+
+    /// The number of times a collapsed line was repeated, e.g. `(×3)`
+    fn repeated(&self, n: usize) -> String {
+        format!("(×{})", n)
+    }
+
+    /// The time since the start shown after the text of a heartbeat line, e.g. `(2m 13s)`
+    fn heartbeat(&self, elapsed: Duration) -> String {
+        format!("({})", HumanDuration::new(elapsed))
+    }
+
+    /// The estimated time until a transfer of [`progress::copy`](crate::progress::copy)
+    /// is done, e.g. `1m 5s left`
+    fn time_left(&self, left: Duration) -> String {
+        format!("{} left", HumanDuration::new(left))
+    }
+
+    /// A duration in words, e.g. `12 seconds`, `about 1 hour`
+    fn duration(&self, duration: Duration) -> String {
+        let secs = duration.as_secs();
+        match secs {
+            0..=4 => String::from("a few seconds"),
+            5..=59 => format!("{} seconds", secs),
+            60..=89 => String::from("about a minute"),
+            90..=3299 => format!("{} minutes", (secs + 30) / 60),
+            3300..=5399 => String::from("about 1 hour"),
+            5400..=172_799 => format!("about {} hours", (secs + 1800) / 3600),
+            _ => format!("about {} days", (secs + 43200) / 86400),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct English;
+
+impl Localizer for English {}
+
+pub(crate) struct Locale(pub(crate) Box<dyn Localizer>);
+
+impl Debug for Locale {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("Locale")
+    }
+}
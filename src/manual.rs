@@ -0,0 +1,57 @@
+use crate::power::Power;
+use crate::render::Renderer;
+use crate::{Signal, Spinner};
+use std::fmt::{self, Debug, Formatter};
+use std::sync::mpsc::Receiver;
+use std::time::Instant;
+
+/// The output and the animation run on the caller's thread, by `Loading::tick`
+pub(crate) struct Manual {
+    receiver: Receiver<Signal>,
+    renderer: Renderer,
+    spinner: Spinner,
+    power: Option<Power>,
+    deadline: Instant,
+    ended: bool,
+}
+
+impl Debug for Manual {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("Manual")
+    }
+}
+
+impl Manual {
+    pub(crate) fn new(receiver: Receiver<Signal>, renderer: Renderer, spinner: Spinner) -> Self {
+        Self {
+            receiver,
+            renderer,
+            power: spinner.power_saving.map(|_| Power::new()),
+            spinner,
+            deadline: Instant::now(),
+            ended: false,
+        }
+    }
+
+    // Show the next frame if it is due, then handle the pending signals
+    pub(crate) fn pump(&mut self, animate: bool) {
+        if self.ended {
+            return;
+        }
+        let now = Instant::now();
+        if animate && now >= self.deadline {
+            let (frame, cycle) = self.spinner.advance();
+            self.renderer.handle(Signal::Frame(frame));
+            if let Some(n) = cycle {
+                self.renderer.handle(Signal::Cycle(n));
+            }
+            self.deadline = now + self.spinner.next_interval(&mut self.power);
+        }
+        while let Ok(signal) = self.receiver.try_recv() {
+            if !self.renderer.handle(signal) {
+                self.ended = true;
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::Spinner;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+impl Spinner {
+    /// Load the spinners defined in the `.toml` and `.json` files of a directory,
+    /// keyed by file name without the extension
+    ///
+    /// Each file has a `frames` list of strings and an optional `interval` in milliseconds:
+    ///
+    /// ```toml
+    /// frames = ["◐", "◓", "◑", "◒"]
+    /// interval = 120
+    /// ```
+    ///
+    /// ```
+    /// use loading::Spinner;
+    /// use std::fs;
+    ///
+    /// let dir = std::env::temp_dir().join("loading-pack-example");
+    /// fs::create_dir_all(&dir).unwrap();
+    /// fs::write(dir.join("dots.json"), r#"{"frames": [".", "..", "..."]}"#).unwrap();
+    ///
+    /// let pack = Spinner::load_pack(&dir).unwrap();
+    /// assert!(pack.contains_key("dots"));
+    /// ```
+    ///
+    /// Files that can't be read or parsed are skipped, see [`Spinner::load_pack_report`]
+    pub fn load_pack<P: AsRef<Path>>(dir: P) -> Result<HashMap<String, Spinner>> {
+        Self::load_pack_report(dir, &mut Vec::new())
+    }
+
+    /// Like [`Spinner::load_pack`], also adding the files that were skipped to `skipped`
+    /// with the reason
+    ///
+    /// ```
+    /// use loading::Spinner;
+    /// use std::fs;
+    ///
+    /// let dir = std::env::temp_dir().join("loading-pack-report-example");
+    /// fs::create_dir_all(&dir).unwrap();
+    /// fs::write(dir.join("dots.json"), r#"{"frames": [".", "..", "..."]}"#).unwrap();
+    /// fs::write(dir.join("broken.toml"), "frames = [").unwrap();
+    ///
+    /// let mut skipped = Vec::new();
+    /// let pack = Spinner::load_pack_report(&dir, &mut skipped).unwrap();
+    /// assert!(pack.contains_key("dots"));
+    /// assert_eq!(skipped.len(), 1);
+    /// ```
+    pub fn load_pack_report<P: AsRef<Path>>(
+        dir: P,
+        skipped: &mut Vec<(PathBuf, Error)>,
+    ) -> Result<HashMap<String, Spinner>> {
+        let mut pack = HashMap::new();
+        for entry in fs::read_dir(dir)? {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(_) => continue,
+            };
+            let ext = path.extension().and_then(|ext| ext.to_str());
+            if !matches!(ext, Some("toml" | "json")) {
+                continue;
+            }
+            let name = match path.file_stem().and_then(|name| name.to_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let spinner = fs::read_to_string(&path).and_then(|text| {
+                parse(&text).ok_or_else(|| {
+                    let msg = format!("invalid spinner definition: {}", path.display());
+                    Error::new(ErrorKind::InvalidData, msg)
+                })
+            });
+            match spinner {
+                Ok(spinner) => {
+                    pack.insert(name, spinner);
+                }
+                Err(e) => skipped.push((path, e)),
+            }
+        }
+        Ok(pack)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Str(String),
+    Word(String),
+    Num(u64),
+    Punct(char),
+}
+
+// Read `frames` and `interval` from the subset of TOML and JSON used by spinner definitions
+fn parse(text: &str) -> Option<Spinner> {
+    let tokens = tokenize(text)?;
+    let mut frames = None;
+    let mut interval = None;
+    let mut i = 0;
+    while i < tokens.len() {
+        let key = match &tokens[i] {
+            Token::Str(key) | Token::Word(key) => key.as_str(),
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        if !matches!(tokens.get(i + 1), Some(Token::Punct('=' | ':'))) {
+            i += 1;
+            continue;
+        }
+        match (key, tokens.get(i + 2)) {
+            ("frames", Some(Token::Punct('['))) => {
+                let mut list = Vec::new();
+                i += 3;
+                loop {
+                    match tokens.get(i)? {
+                        Token::Str(s) => list.push(s.clone()),
+                        Token::Punct(',') => {}
+                        Token::Punct(']') => break,
+                        _ => return None,
+                    }
+                    i += 1;
+                }
+                frames = Some(list);
+            }
+            ("interval", Some(Token::Num(n))) => {
+                interval = Some(Duration::from_millis(*n));
+                i += 2;
+            }
+            _ => i += 2,
+        }
+    }
+
+    let frames = frames.filter(|frames| !frames.is_empty())?;
+    let mut spinner = Spinner::with_frames(frames);
+    if let Some(interval) = interval {
+        spinner.interval(interval);
+    }
+    Some(spinner)
+}
+
+// The character of an escape in a double-quoted string, after the backslash
+fn unescape<I: Iterator<Item = char>>(chars: &mut I) -> Option<char> {
+    Some(match chars.next()? {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        'b' => '\u{8}',
+        'f' => '\u{c}',
+        'e' => '\u{1b}',
+        'u' => {
+            let high = hex(chars, 4)?;
+            if !(0xD800..0xDC00).contains(&high) {
+                return char::from_u32(high);
+            }
+            // JSON writes the characters outside the BMP as a surrogate pair
+            if chars.next()? != '\\' || chars.next()? != 'u' {
+                return None;
+            }
+            let low = hex(chars, 4)?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return None;
+            }
+            char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))?
+        }
+        // TOML
+        'U' => char::from_u32(hex(chars, 8)?)?,
+        c => c,
+    })
+}
+
+// The value of the next `len` hex digits
+fn hex<I: Iterator<Item = char>>(chars: &mut I, len: usize) -> Option<u32> {
+    let hex = chars.take(len).collect::<String>();
+    match hex.len() == len {
+        true => u32::from_str_radix(&hex, 16).ok(),
+        false => None,
+    }
+}
+
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '#' => while chars.next_if(|&c| c != '\n').is_some() {},
+            '\'' => {
+                let s = (&mut chars).take_while(|&c| c != '\'').collect();
+                tokens.push(Token::Str(s));
+            }
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => s.push(unescape(&mut chars)?),
+                        c => s.push(c),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            '0'..='9' => {
+                let mut n = c.to_digit(10)? as u64;
+                while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '_') {
+                    if let Some(d) = c.to_digit(10) {
+                        n = n.checked_mul(10)?.checked_add(d as u64)?;
+                    }
+                }
+                tokens.push(Token::Num(n));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut s = String::from(c);
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    s.push(c);
+                }
+                tokens.push(Token::Word(s));
+            }
+            c => tokens.push(Token::Punct(c)),
+        }
+    }
+    Some(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(text: &str) -> Option<Vec<String>> {
+        parse(text).map(|spinner| spinner.frames)
+    }
+
+    #[test]
+    fn toml() {
+        let spinner = parse("# moon\nframes = ['◐', \"◓\"] # two\ninterval = 1_000\n").unwrap();
+        assert_eq!(spinner.frames, ["◐", "◓"]);
+        assert_eq!(spinner.interval, Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn json() {
+        let spinner = parse(r#"{"interval": 80, "frames": ["a", "b",]}"#).unwrap();
+        assert_eq!(spinner.frames, ["a", "b"]);
+        assert_eq!(spinner.interval, Duration::from_millis(80));
+    }
+
+    #[test]
+    fn escapes() {
+        let text = r#"{"frames": ["\n\t\r\b\f\"\\\/", "\u00e9", "\uD83D\uDE00", "\U0001F600"]}"#;
+        assert_eq!(
+            frames(text).unwrap(),
+            ["\n\t\r\u{8}\u{c}\"\\/", "é", "😀", "😀"]
+        );
+        assert_eq!(frames(r#"{"frames": ["\uD83D"]}"#), None);
+        assert_eq!(frames(r#"{"frames": ["\uD83Dx"]}"#), None);
+        assert_eq!(frames(r#"{"frames": ["\u12"]}"#), None);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(frames(""), None);
+        assert_eq!(frames("frames = []"), None);
+        assert_eq!(frames("frames = ["), None);
+        assert_eq!(frames("frames = [1, 2]"), None);
+        assert_eq!(frames(r#"{"frames": ["open]}"#), None);
+    }
+
+    #[test]
+    fn skip_bad_files() {
+        let dir = std::env::temp_dir().join(format!("loading-pack-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("good.toml"), "frames = ['-']").unwrap();
+        fs::write(dir.join("bad.json"), "{").unwrap();
+        fs::write(dir.join("notes.txt"), "{").unwrap();
+
+        let mut skipped = Vec::new();
+        let pack = Spinner::load_pack_report(&dir, &mut skipped).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(pack.keys().collect::<Vec<_>>(), ["good"]);
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].0.ends_with("bad.json"));
+        assert_eq!(skipped[0].1.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::{Duration, Instant};
+
+// How often the power state is checked again
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Caches whether the system asks to save power
+#[derive(Debug)]
+pub(crate) struct Power {
+    saving: bool,
+    checked: Instant,
+}
+
+impl Power {
+    pub(crate) fn new() -> Self {
+        Self {
+            saving: detect(),
+            checked: Instant::now(),
+        }
+    }
+
+    pub(crate) fn saving(&mut self) -> bool {
+        if self.checked.elapsed() >= CHECK_INTERVAL {
+            self.saving = detect();
+            self.checked = Instant::now();
+        }
+        self.saving
+    }
+}
+
+// Without the `power` feature the slow interval is always used
+#[cfg(not(feature = "power"))]
+fn detect() -> bool {
+    true
+}
+
+#[cfg(all(feature = "power", target_os = "linux"))]
+fn detect() -> bool {
+    on_battery() || niced()
+}
+
+// Neither the battery nor the niceness is detected on other systems yet
+#[cfg(all(feature = "power", not(target_os = "linux")))]
+fn detect() -> bool {
+    false
+}
+
+#[cfg(all(feature = "power", target_os = "linux"))]
+fn on_battery() -> bool {
+    let entries = match std::fs::read_dir("/sys/class/power_supply") {
+        Ok(entries) => entries,
+        Err(_) => return false,
+    };
+    entries.flatten().any(|entry| {
+        let read = |name| std::fs::read_to_string(entry.path().join(name)).unwrap_or_default();
+        read("type").trim() == "Battery" && read("status").trim() == "Discharging"
+    })
+}
+
+#[cfg(all(feature = "power", target_os = "linux"))]
+fn niced() -> bool {
+    // The fields after the command name, which is in parentheses
+    let stat = std::fs::read_to_string("/proc/self/stat").unwrap_or_default();
+    let fields = match stat.rsplit_once(')') {
+        Some((_, fields)) => fields,
+        None => return false,
+    };
+    // `nice` is the 19th field, the 17th after the command name
+    fields
+        .split_whitespace()
+        .nth(16)
+        .and_then(|n| n.parse::<i32>().ok())
+        .is_some_and(|n| n > 0)
+}
@@ -0,0 +1,306 @@
+//! Show the bytes copied between a reader and a writer
+
+use crate::{Loading, Localizer};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+// How often `copy` updates the loading
+const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+
+// The numbers shown by `copy`, kept for `Loading::position` and `Loading::rate`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Measure {
+    pub(crate) done: u64,
+    pub(crate) total: Option<u64>,
+    // Bytes per second
+    pub(crate) rate: f64,
+}
+
+impl Measure {
+    pub(crate) fn new(done: u64, total: Option<u64>, elapsed: Duration) -> Self {
+        Self {
+            done,
+            total,
+            rate: done as f64 / elapsed.as_secs_f64().max(0.001),
+        }
+    }
+
+    pub(crate) fn percent(&self) -> Option<u64> {
+        let total = self.total.filter(|total| *total > 0)?;
+        Some((self.done * 100 / total).min(100))
+    }
+}
+
+/// Something that counts the bytes passing through it, see [`copy`]
+pub trait Progress {
+    /// The bytes transferred so far
+    fn transferred(&self) -> u64;
+
+    /// The total number of bytes, when known
+    fn total(&self) -> Option<u64>;
+}
+
+/// A reader that counts the bytes read
+#[derive(Debug)]
+pub struct ProgressRead<R> {
+    inner: R,
+    read: u64,
+    total: Option<u64>,
+}
+
+impl<R: Read> ProgressRead<R> {
+    /// Wrap a reader, `total` is the expected length, e.g. from a `Content-Length` header
+    pub fn new(inner: R, total: Option<u64>) -> Self {
+        Self {
+            inner,
+            read: 0,
+            total,
+        }
+    }
+
+    /// Get back the wrapped reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl ProgressRead<File> {
+    /// Wrap a file, the total is its size
+    pub fn file(file: File) -> Self {
+        let total = file.metadata().ok().map(|metadata| metadata.len());
+        Self::new(file, total)
+    }
+}
+
+impl<R: Read> Read for ProgressRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R> Progress for ProgressRead<R> {
+    fn transferred(&self) -> u64 {
+        self.read
+    }
+
+    fn total(&self) -> Option<u64> {
+        self.total
+    }
+}
+
+/// A writer that counts the bytes written
+#[derive(Debug)]
+pub struct ProgressWrite<W> {
+    inner: W,
+    written: u64,
+    total: Option<u64>,
+}
+
+impl<W: Write> ProgressWrite<W> {
+    /// Wrap a writer, `total` is the expected length
+    pub fn new(inner: W, total: Option<u64>) -> Self {
+        Self {
+            inner,
+            written: 0,
+            total,
+        }
+    }
+
+    /// Get back the wrapped writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for ProgressWrite<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W> Progress for ProgressWrite<W> {
+    fn transferred(&self) -> u64 {
+        self.written
+    }
+
+    fn total(&self) -> Option<u64> {
+        self.total
+    }
+}
+
+/// Copy all of `reader` to `writer` like [`std::io::copy`], showing the bytes copied
+/// as the right text: the percentage and the time left when the total is known, and the rate
+///
+/// The rate leaves out the time the loading is paused, and the time left is worded
+/// by the [`Localizer`] of the loading
+///
+/// ```
+/// use loading::{progress, Loading};
+/// use loading::progress::ProgressRead;
+///
+/// let loading = Loading::default();
+/// loading.text("Copying");
+/// let mut reader = ProgressRead::new(&[0u8; 4096][..], Some(4096));
+/// let copied = progress::copy(&mut reader, &mut std::io::sink(), &loading).unwrap();
+/// assert_eq!(copied, 4096);
+/// loading.end();
+/// ```
+pub fn copy<R, W>(reader: &mut R, writer: &mut W, loading: &Loading) -> io::Result<u64>
+where
+    R: Read + Progress + ?Sized,
+    W: Write + ?Sized,
+{
+    let start = loading.elapsed();
+    loading.transfer(Some((reader.transferred(), reader.total())));
+    let result = transfer(reader, writer, loading, start);
+    let elapsed = loading.elapsed().saturating_sub(start);
+    loading.set_measure(Measure::new(reader.transferred(), reader.total(), elapsed));
+    if result.is_ok() {
+        // The last percentage, for the milestones
+        loading.transfer(Some((reader.transferred(), reader.total())));
+    }
+    // Don't leave the rate of a failed copy on the line either
+    loading.transfer(None);
+    result
+}
+
+fn transfer<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    loading: &Loading,
+    start: Duration,
+) -> io::Result<u64>
+where
+    R: Read + Progress + ?Sized,
+    W: Write + ?Sized,
+{
+    let mut last = Instant::now();
+    let mut buf = [0; 64 * 1024];
+    let mut copied = 0;
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all(&buf[..n])?;
+        copied += n as u64;
+        if last.elapsed() >= UPDATE_INTERVAL {
+            last = Instant::now();
+            let elapsed = loading.elapsed().saturating_sub(start);
+            let (done, total) = (reader.transferred(), reader.total());
+            loading.set_measure(Measure::new(done, total, elapsed));
+            loading.transfer(Some((done, total)));
+        }
+        // Nothing else writes the updates without the output thread
+        #[cfg(feature = "no-threads")]
+        loading.tick();
+    }
+    Ok(copied)
+}
+
+// e.g. `35% · 1.2 MiB / 3.4 MiB · 2.1 MiB/s · 1s left`
+pub(crate) fn status(measure: Measure, locale: &dyn Localizer) -> String {
+    let Measure { done, total, rate } = measure;
+    match total {
+        Some(total) if total > 0 => {
+            let left = Duration::from_secs_f64(total.saturating_sub(done) as f64 / rate.max(1.0));
+            format!(
+                "{}% · {} / {} · {}/s · {}",
+                done * 100 / total,
+                bytes(done),
+                bytes(total),
+                bytes(rate as u64),
+                locale.time_left(left)
+            )
+        }
+        _ => format!("{} · {}/s", bytes(done), bytes(rate as u64)),
+    }
+}
+
+fn bytes(n: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if n < 1024 {
+        return format!("{} B", n);
+    }
+    let mut value = n as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Spinner;
+
+    // Slow enough for `copy` to show the rate, then fails
+    struct Failing(usize);
+
+    impl Read for Failing {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            std::thread::sleep(UPDATE_INTERVAL);
+            self.0 += 1;
+            match self.0 {
+                1..=2 => Ok(buf.len().min(100)),
+                _ => Err(io::Error::other("connection reset")),
+            }
+        }
+    }
+
+    #[test]
+    fn clear_rate_on_error() {
+        let loading = Loading::with_writer(io::sink(), Spinner::default());
+        loading.text("Copying");
+        let mut reader = ProgressRead::new(Failing(0), Some(1000));
+        assert!(copy(&mut reader, &mut io::sink(), &loading).is_err());
+        let mut line = Vec::new();
+        loading.draw_into(&mut line).unwrap();
+        assert!(!String::from_utf8(line).unwrap().contains('%'));
+        assert_eq!(loading.position(), Some(200));
+        loading.end();
+    }
+
+    #[cfg(feature = "no-threads")]
+    #[test]
+    fn written_without_threads() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone)]
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Buffer(Arc::default());
+        let loading = Loading::with_writer(buffer.clone(), Spinner::default());
+        loading.force_ansi();
+        loading.text("Copying");
+        let mut reader = ProgressRead::new(&[0u8; 16][..], Some(16));
+        copy(&mut reader, &mut io::sink(), &loading).unwrap();
+        // Written by copy itself, before anything else pumps the loading
+        assert!(String::from_utf8_lossy(&buffer.0.lock().unwrap()).contains("Copying"));
+        loading.end();
+    }
+}
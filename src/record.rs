@@ -0,0 +1,91 @@
+use crate::render::json_escape;
+use crate::transcript::{escape, unescape};
+use crate::{Loading, Signal};
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind, Result, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+/// The timed output of a loading written by [`Loading::record`]
+///
+/// ```no_run
+/// use loading::{Loading, Recording};
+///
+/// let loading = Loading::default();
+/// loading.record("/tmp/session.rec").unwrap();
+/// loading.success("Done");
+/// loading.end();
+///
+/// let recording = Recording::load("/tmp/session.rec").unwrap();
+/// recording.replay(&mut std::io::stdout()).unwrap();
+/// std::fs::write("/tmp/session.cast", recording.to_cast(80, 24)).unwrap();
+/// std::fs::write("/tmp/session.svg", recording.to_svg(80, 24)).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    pub(crate) chunks: Vec<(Duration, String)>,
+}
+
+impl Loading {
+    /// Write everything the loading outputs from now on to a file, with the time of each write
+    pub fn record<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let file = File::create(path)?;
+        let _ = self.sender.send(Signal::Record(file));
+        Ok(())
+    }
+}
+
+impl Recording {
+    /// Read a recording written by [`Loading::record`]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let mut chunks = Vec::new();
+        for row in content.lines() {
+            let (time, output) = row.split_once('\t').ok_or_else(|| invalid(row))?;
+            let time = time.parse().map_err(|_| invalid(row))?;
+            chunks.push((Duration::from_millis(time), unescape(output)));
+        }
+        Ok(Self { chunks })
+    }
+
+    /// Play the output back with its original timing
+    pub fn replay<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let mut last = Duration::ZERO;
+        for (time, output) in &self.chunks {
+            thread::sleep(time.saturating_sub(last));
+            last = *time;
+            writer.write_all(output.as_bytes())?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Convert to an [asciinema](https://asciinema.org) cast file (version 2)
+    pub fn to_cast(&self, width: u16, height: u16) -> String {
+        let mut cast = format!(
+            "{{\"version\":2,\"width\":{},\"height\":{}}}\n",
+            width, height
+        );
+        for (time, output) in &self.chunks {
+            cast.push_str(&format!(
+                "[{:.3},\"o\",\"{}\"]\n",
+                time.as_secs_f64(),
+                json_escape(output)
+            ));
+        }
+        cast
+    }
+}
+
+// Append one write of the output to a recording file
+pub(crate) fn write(file: &mut File, time: Duration, output: &str) {
+    let _ = writeln!(file, "{}\t{}", time.as_millis(), escape(output));
+}
+
+fn invalid(row: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid recording line: {:?}", row),
+    )
+}
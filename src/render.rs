@@ -0,0 +1,1504 @@
+use crate::clock::Clock;
+use crate::locale::{English, Locale, Localizer};
+use crate::progress::{self, Measure};
+use crate::record;
+use crate::redact::Redactor;
+use crate::state::{columns, layout};
+use crate::summary::{Callback, CycleCallback};
+use crate::terminal;
+use crate::{
+    Dedup, HumanDuration, Mode, Output, Signal, Spinner, State, Status, Summary, Theme, Transcript,
+};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// State of the output thread
+pub(crate) struct Renderer {
+    output: Output,
+    mode: Mode,
+    // The frame, the texts, the indent and the icon of the current line
+    state: State,
+    frozen: bool,
+    // The terminal rows printed above the current line in ANSI mode, long lines take several
+    rows: usize,
+    // Write the colors and the styles, `false` when `NO_COLOR` is set
+    colors: bool,
+    // Used instead of the width of the output when set
+    width: Option<usize>,
+    history: Vec<Line>,
+    dim_after: Option<usize>,
+    timing: bool,
+    slow: Option<(Duration, Duration)>,
+    // Clock time when the current step started
+    step: Duration,
+    group: Option<Group>,
+    // Print the percentage every `milestones` percent in the modes without animation
+    milestones: u8,
+    // The last milestone printed
+    milestone: Option<u8>,
+    // The clock time when the current `progress::copy` started
+    transfer: Option<Duration>,
+    // The number of sections opened so far, the id of the next group
+    sections: usize,
+    dedup: Option<(usize, Dedup)>,
+    throttle: Option<Duration>,
+    withheld: Option<Vec<Line>>,
+    // Whether the most recent status line is the last withheld one
+    last_withheld: bool,
+    // The number of the next status line, when numbering is enabled
+    sequence: Option<usize>,
+    recent_info: HashMap<String, Instant>,
+    redactor: Redactor,
+    escape: bool,
+    marquee: Option<Duration>,
+    text_start: Instant,
+    clock: Clock,
+    on_finish: Vec<Callback>,
+    on_cycle: Vec<CycleCallback>,
+    fail_code: Option<u8>,
+    details: Vec<String>,
+    defer: Defer,
+    // The columns of the prefix before the line in inline mode
+    inline: Option<usize>,
+    tee: Option<Output>,
+    recap: bool,
+    health: Option<Option<Status>>,
+    heartbeat: Option<Duration>,
+    // Texts to show when their flag is raised
+    shutdown: Vec<(Arc<AtomicBool>, String)>,
+    // A shutdown text was printed, the line is no longer drawn
+    shut_down: bool,
+    budget: Option<usize>,
+    recorder: Option<(Instant, File)>,
+    // The start of the current second and the bytes written since
+    written: (Instant, usize),
+    pulse: Option<Duration>,
+    highlight: Option<Duration>,
+    theme: Theme,
+    locale: Locale,
+    // The range of characters that changed in the last text update
+    changed: (usize, usize),
+    last_print: Instant,
+}
+
+impl Renderer {
+    pub(crate) fn new(output: Output, mode: Mode) -> Self {
+        Self {
+            output,
+            mode,
+            state: State::blank(Spinner::default()),
+            frozen: false,
+            rows: 0,
+            colors: !terminal::no_color(),
+            width: None,
+            history: Vec::new(),
+            dim_after: None,
+            timing: false,
+            slow: None,
+            step: Duration::ZERO,
+            group: None,
+            sections: 0,
+            milestones: 10,
+            milestone: None,
+            transfer: None,
+            dedup: None,
+            throttle: None,
+            withheld: None,
+            last_withheld: false,
+            sequence: None,
+            recent_info: HashMap::new(),
+            redactor: Redactor::default(),
+            escape: true,
+            marquee: None,
+            text_start: Instant::now(),
+            clock: Clock::new(),
+            on_finish: Vec::new(),
+            on_cycle: Vec::new(),
+            fail_code: None,
+            details: Vec::new(),
+            defer: Defer::None,
+            inline: None,
+            tee: None,
+            recap: false,
+            health: None,
+            heartbeat: None,
+            shutdown: Vec::new(),
+            shut_down: false,
+            budget: None,
+            recorder: None,
+            written: (Instant::now(), 0),
+            pulse: None,
+            highlight: None,
+            theme: Theme::default(),
+            locale: Locale(Box::new(English)),
+            changed: (0, 0),
+            last_print: Instant::now(),
+        }
+    }
+
+    /// Handle a signal, return `false` when the output thread should exit
+    pub(crate) fn handle(&mut self, signal: Signal) -> bool {
+        self.poll_shutdown();
+        match self.clean(signal) {
+            Signal::Frame(_) if self.frozen => {
+                self.beat();
+                // Only the marquee and the pulse still move
+                if self.marquee.is_some() || self.pulse.is_some() {
+                    self.draw();
+                }
+            }
+            Signal::Frame(s) => {
+                let first = self.state.frame.is_empty();
+                self.state.frame = s;
+                match self.defer {
+                    Defer::Tick if !first => self.defer = Defer::None,
+                    Defer::Tick | Defer::Text => return true,
+                    Defer::None => {}
+                }
+                self.beat();
+                self.draw();
+            }
+            Signal::Text(s) => {
+                if self.mode == Mode::Verbose && s != self.state.text && !s.is_empty() {
+                    let line = format!("{}{}{}\n", self.state.indent, self.state.icon, s);
+                    self.print(&line);
+                }
+                if s != self.state.text {
+                    self.text_start = Instant::now();
+                    self.changed = changed(&self.state.text, &s);
+                }
+                if !s.is_empty() {
+                    self.defer = Defer::None;
+                }
+                self.state.text = s;
+                self.draw();
+            }
+            Signal::RightText(s) => {
+                self.state.right = s;
+                self.draw();
+            }
+            Signal::Next(status, s) => {
+                self.handle_next(status, s);
+            }
+            Signal::Amend(status, s) => {
+                self.amend(status, s);
+            }
+            Signal::Section(s) => {
+                self.end_section();
+                self.persist(Line::new(Kind::Section, self.state.indent.clone(), s));
+                self.sections += 1;
+                self.group = Some(Group {
+                    id: self.sections,
+                    start: self.clock.elapsed(),
+                    indent: self.state.indent.clone(),
+                    counts: [0; 4],
+                });
+            }
+            Signal::Draw(sender) => {
+                let line = match self.defer {
+                    Defer::None => self.line(),
+                    _ => String::new(),
+                };
+                let line = match self.colors {
+                    true => line,
+                    false => strip_colors(&line),
+                };
+                let _ = sender.send(line);
+            }
+            Signal::Transcript(sender) => {
+                let _ = sender.send(Transcript {
+                    lines: self.history.clone(),
+                });
+            }
+            Signal::Restore(transcript) => {
+                for line in transcript.lines {
+                    self.persist(line);
+                }
+            }
+            #[cfg(all(unix, not(feature = "no-threads")))]
+            Signal::Child(kind, depth, s) => {
+                let prefix = format!("{}{}", self.state.indent, "  ".repeat(depth + 1));
+                self.persist(Line::new(kind, prefix, s));
+            }
+            Signal::EndSection => {
+                self.end_section();
+            }
+            Signal::DimAfter(n) => {
+                self.dim_after = Some(n);
+            }
+            Signal::Dedup(window, rule) => {
+                self.dedup = Some((window, rule));
+            }
+            Signal::DeferStatus(true) => {
+                self.withheld.get_or_insert_with(Vec::new);
+            }
+            Signal::DeferStatus(false) => {
+                self.flush_withheld();
+            }
+            Signal::Sequence(enable) => {
+                self.sequence = enable.then_some(self.sequence.unwrap_or(1));
+            }
+            Signal::ThrottleInfo(interval) => {
+                self.throttle = Some(interval);
+            }
+            Signal::Redact(secret) => {
+                self.redactor.add(secret);
+            }
+            Signal::RedactUrls(enable) => {
+                self.redactor.urls(enable);
+            }
+            Signal::EscapeControl(enable) => {
+                self.escape = enable;
+            }
+            Signal::Milestones(step) => {
+                self.milestones = step;
+            }
+            #[cfg(any(test, feature = "compat"))]
+            Signal::Percent(percent) => {
+                self.state.set_position(percent as u64, Some(100));
+                self.milestone();
+            }
+            Signal::Transfer(Some((done, total))) => {
+                let now = self.clock.elapsed();
+                let start = *self.transfer.get_or_insert(now);
+                let measure = Measure::new(done, total, now.saturating_sub(start));
+                self.state.right = progress::status(measure, &*self.locale.0);
+                if let Some(percent) = measure.percent() {
+                    self.state.set_position(percent, Some(100));
+                    self.milestone();
+                }
+                self.draw();
+            }
+            Signal::Transfer(None) => {
+                self.transfer = None;
+                self.state.right.clear();
+                self.draw();
+            }
+            Signal::Marquee(step) => {
+                self.marquee = Some(step);
+            }
+            Signal::Locale(locale) => {
+                self.locale = locale;
+            }
+            Signal::Theme(theme) => {
+                self.theme = theme;
+            }
+            Signal::HighlightChanges(duration) => {
+                self.highlight = Some(duration);
+            }
+            Signal::Record(file) => {
+                self.recorder = Some((Instant::now(), file));
+            }
+            Signal::ByteBudget(bytes) => {
+                self.budget = Some(bytes);
+            }
+            Signal::Pulse(after) => {
+                self.pulse = Some(after);
+            }
+            Signal::Heartbeat(interval) => {
+                self.heartbeat = Some(interval);
+            }
+            Signal::Detail(s) => {
+                self.details.extend(s.lines().map(String::from));
+            }
+            Signal::Health(enable) => {
+                self.health = enable.then_some(None);
+                self.draw();
+            }
+            Signal::Recap(enable) => {
+                self.recap = enable;
+            }
+            Signal::Tee(output) => {
+                self.tee = Some(output);
+            }
+            Signal::Defer(defer) => {
+                self.defer = defer;
+            }
+            Signal::Mode(mode) => {
+                self.clear();
+                self.mode = mode;
+                self.draw();
+            }
+            Signal::ShutdownFlag(flag, text) => {
+                self.shutdown.push((flag, text));
+            }
+            #[cfg(not(feature = "no-threads"))]
+            Signal::Stalled => {
+                // Without frames the line would stay frozen, print the following lines plainly
+                self.clear();
+                if self.mode == Mode::Ansi {
+                    self.mode = Mode::Plain;
+                }
+            }
+            Signal::Reset => {
+                self.reset();
+            }
+            Signal::Freeze => {
+                self.frozen = true;
+            }
+            Signal::Pause => {
+                self.clock.pause();
+                self.clear();
+            }
+            Signal::Resume => {
+                self.clock.resume();
+                self.draw();
+            }
+            Signal::WallClock(enable) => {
+                self.clock.wall_clock(enable);
+            }
+            Signal::Inline(prefix) => {
+                if self.mode == Mode::Ansi {
+                    // Save the cursor position to come back to on every redraw
+                    self.print("\x1B7");
+                    self.inline = Some(prefix);
+                }
+            }
+            Signal::OnFinish(callback) => {
+                self.on_finish.push(callback);
+            }
+            Signal::FailExitCode(code) => {
+                self.fail_code = Some(code);
+            }
+            Signal::OnCycle(callback) => {
+                self.on_cycle.push(callback);
+            }
+            Signal::Cycle(n) => {
+                for CycleCallback(f) in &mut self.on_cycle {
+                    f(n);
+                }
+            }
+            Signal::Timing(enable) => {
+                self.timing = enable;
+            }
+            Signal::SlowStep(warn, fail) => {
+                self.slow = Some((warn, fail));
+            }
+            Signal::Icon(s) => {
+                self.state.set_icon(s);
+                self.draw();
+            }
+            Signal::Indent(n) => {
+                self.state.set_indent(n);
+                self.draw();
+            }
+            #[cfg(all(any(test, feature = "testing"), not(feature = "no-threads")))]
+            Signal::Width(width) => {
+                self.width = width;
+                self.draw();
+            }
+            Signal::Exit(sender) => {
+                self.flush_withheld();
+                self.end_section();
+                if self.recap {
+                    self.print_recap();
+                }
+                self.clear();
+                if !self.on_finish.is_empty() {
+                    let summary = self.summary();
+                    for Callback(f) in self.on_finish.drain(..) {
+                        f(&summary);
+                    }
+                }
+                let _ = sender.send(());
+                return false;
+            }
+        }
+        true
+    }
+
+    // Clear the line after the output thread panicked
+    #[cfg(not(feature = "no-threads"))]
+    pub(crate) fn abandon(&mut self) {
+        self.clear();
+    }
+
+    // Forget the lines, the sections and the timers, but keep the settings
+    fn reset(&mut self) {
+        self.history.clear();
+        self.details.clear();
+        self.recent_info.clear();
+        self.group = None;
+        if let Some(withheld) = &mut self.withheld {
+            withheld.clear();
+        }
+        self.last_withheld = false;
+        if self.sequence.is_some() {
+            self.sequence = Some(1);
+        }
+        if self.health.is_some() {
+            self.health = Some(None);
+        }
+        self.clock.reset();
+        self.step = Duration::ZERO;
+        self.state.text.clear();
+        self.state.right.clear();
+        self.text_start = Instant::now();
+        self.changed = (0, 0);
+        self.draw();
+    }
+
+    fn summary(&self) -> Summary {
+        let mut summary = Summary {
+            duration: self.clock.elapsed(),
+            fail_code: self.fail_code,
+            ..Summary::default()
+        };
+        for line in &self.history {
+            if let Kind::Status(status) = line.kind {
+                summary.counts[status as usize] += line.count;
+                summary.status = Some(status);
+            }
+        }
+        summary
+    }
+
+    // Print the failed and warned lines grouped together
+    fn print_recap(&mut self) {
+        for status in [Status::Fail, Status::Warn] {
+            let texts = self
+                .history
+                .iter()
+                .filter(|line| matches!(line.kind, Kind::Status(s) if s == status))
+                .map(|line| line.text.as_str())
+                .collect::<Vec<_>>();
+            if !texts.is_empty() {
+                let text = format!("{}: {}", self.locale.0.recap(status), texts.join(", "));
+                self.persist(Line::new(Kind::Summary, String::new(), text));
+            }
+        }
+    }
+
+    // Escape the control characters and mask the secrets in the text of a signal
+    fn clean(&self, signal: Signal) -> Signal {
+        let clean = |s: String| self.redactor.apply(self.escape(s, false));
+        match signal {
+            Signal::Text(s) => Signal::Text(clean(s)),
+            Signal::RightText(s) => Signal::RightText(clean(s)),
+            Signal::Next(status, s) => Signal::Next(status, clean(s)),
+            Signal::Amend(status, s) => Signal::Amend(status, clean(s)),
+            Signal::Section(s) => Signal::Section(clean(s)),
+            #[cfg(all(unix, not(feature = "no-threads")))]
+            Signal::Child(kind, depth, s) => Signal::Child(kind, depth, clean(s)),
+            Signal::Detail(s) => Signal::Detail(self.redactor.apply(self.escape(s, true))),
+            signal => signal,
+        }
+    }
+
+    fn escape(&self, text: String, newlines: bool) -> String {
+        match self.escape {
+            true => escape_control(text, newlines),
+            false => text,
+        }
+    }
+
+    fn handle_next(&mut self, status: Status, text: String) {
+        if status == Status::Info && self.throttled(&text) {
+            return;
+        }
+        let text = match &mut self.sequence {
+            Some(n) => {
+                *n += 1;
+                format!("#{} {}", *n - 1, text)
+            }
+            None => text,
+        };
+        self.update_health(status);
+        if let Some(group) = &mut self.group {
+            group.counts[status as usize] += 1;
+        }
+        if let Some(i) = self.find_duplicate(status, &text) {
+            self.history[i].count += 1;
+            self.details.clear();
+            return self.rewrite(i, false);
+        }
+        let line = Line {
+            kind: Kind::Status(status),
+            prefix: format!("{}{}", self.state.indent, self.state.icon),
+            text,
+            duration: self
+                .timing
+                .then(|| self.clock.elapsed().saturating_sub(self.step)),
+            slow: self.slow,
+            count: 1,
+            group: self.group.as_ref().map(|group| group.id),
+            row: 0,
+            rows: 0,
+        };
+        self.step = self.clock.elapsed();
+        match &mut self.withheld {
+            Some(withheld) if matches!(status, Status::Info | Status::Warn) => {
+                withheld.push(line);
+                self.last_withheld = true;
+                self.details.clear();
+            }
+            _ => {
+                self.persist(line);
+                self.flush_details(status);
+            }
+        }
+    }
+
+    // Print the withheld 'info' and 'warn' lines
+    fn flush_withheld(&mut self) {
+        for line in self.withheld.take().unwrap_or_default() {
+            self.persist(line);
+        }
+    }
+
+    // Whether an identical 'info' line was printed within the throttle interval
+    fn throttled(&mut self, text: &str) -> bool {
+        let interval = match self.throttle {
+            Some(interval) => interval,
+            None => return false,
+        };
+        let now = Instant::now();
+        self.recent_info.retain(|_, at| now - *at < interval);
+        if self.recent_info.contains_key(text) {
+            return true;
+        }
+        self.recent_info.insert(text.to_string(), now);
+        false
+    }
+
+    // A 'warn' or 'fail' colors the spinner until the next 'success'
+    fn update_health(&mut self, status: Status) {
+        if let Some(health) = &mut self.health {
+            match status {
+                Status::Success => *health = None,
+                Status::Fail => *health = Some(Status::Fail),
+                Status::Warn if *health != Some(Status::Fail) => *health = Some(Status::Warn),
+                _ => {}
+            }
+        }
+    }
+
+    // Print the details under a 'fail' line, discard them otherwise
+    fn flush_details(&mut self, status: Status) {
+        let details = std::mem::take(&mut self.details);
+        if status != Status::Fail {
+            return;
+        }
+        let prefix = format!("{}    ", self.state.indent);
+        for detail in details {
+            self.persist(Line::new(Kind::Detail, prefix.clone(), detail));
+        }
+    }
+
+    // Find a recent line that the new status line should be collapsed into
+    fn find_duplicate(&self, status: Status, text: &str) -> Option<usize> {
+        let (window, rule) = self.dedup?;
+        let start = self.history.len().saturating_sub(window);
+        (start..self.history.len()).rev().find(|&i| {
+            let line = &self.history[i];
+            matches!(line.kind, Kind::Status(s) if s == status) && rule.matches(&line.text, text)
+        })
+    }
+
+    // Redraw the current line
+    fn draw(&mut self) {
+        if self.mode != Mode::Ansi
+            || self.defer != Defer::None
+            || self.clock.is_paused()
+            || self.shut_down
+        {
+            return;
+        }
+        if self.over_budget() {
+            return;
+        }
+        let line = self.line();
+        self.write(&line);
+    }
+
+    // The spinner frame and the text
+    fn line(&self) -> String {
+        let frame = match self.health {
+            Some(Some(status)) => {
+                format!("{}{}\x1B[0m", self.theme.color(status), self.state.frame)
+            }
+            _ => self.state.frame.clone(),
+        };
+        let prefix = format!("{}{}{} ", self.state.indent, self.state.icon, frame);
+        let right_width = match columns(&self.state.right) {
+            0 => 0,
+            n => n + 1,
+        };
+        let left = match self.marquee {
+            Some(step) => self.scroll(columns(&prefix) + right_width, step),
+            None => self.highlighted() + &self.pulse(),
+        };
+        layout(&prefix, &left, &self.state.right, self.line_width())
+    }
+
+    // The columns available for the line
+    fn width(&self) -> usize {
+        self.width.or_else(|| self.output.width()).unwrap_or(80)
+    }
+
+    // The columns available for the current line, after the prefix in inline mode
+    fn line_width(&self) -> usize {
+        self.width().saturating_sub(self.inline.unwrap_or(0))
+    }
+
+    // The text with the recently changed part in bold
+    fn highlighted(&self) -> String {
+        let (start, end) = self.changed;
+        match self.highlight {
+            Some(duration) if start < end && self.text_start.elapsed() < duration => {
+                let chars = self.state.text.chars().collect::<Vec<_>>();
+                let part = |range: &[char]| range.iter().collect::<String>();
+                format!(
+                    "{}\x1B[1m{}\x1B[0m{}",
+                    part(&chars[..start]),
+                    part(&chars[start..end]),
+                    part(&chars[end..])
+                )
+            }
+            _ => self.state.text.clone(),
+        }
+    }
+
+    // A dot that blinks every half second while the text is unchanged
+    fn pulse(&self) -> String {
+        let idle = self.text_start.elapsed();
+        match self.pulse {
+            Some(after) if idle >= after && (idle.as_millis() / 500).is_multiple_of(2) => {
+                String::from(" \x1B[2m.\x1B[0m")
+            }
+            _ => String::new(),
+        }
+    }
+
+    // The visible part of the text, scrolling when it doesn't fit after the prefix
+    fn scroll(&self, prefix: usize, step: Duration) -> String {
+        let width = self.line_width().saturating_sub(prefix + 1);
+        let len = self.state.text.chars().count();
+        if len <= width {
+            return self.state.text.clone();
+        }
+        let gap = "   ";
+        let cycle = len + gap.len();
+        let steps = self.text_start.elapsed().as_millis() / step.as_millis().max(1);
+        let offset = (steps % cycle as u128) as usize;
+        self.state
+            .text
+            .chars()
+            .chain(gap.chars())
+            .cycle()
+            .skip(offset)
+            .take(width)
+            .collect()
+    }
+
+    // Print the text of a raised shutdown flag in place of the line, and stop drawing the line
+    // so nothing is left half-drawn if the process exits right after
+    fn poll_shutdown(&mut self) {
+        if self.shut_down {
+            return;
+        }
+        let raised = self
+            .shutdown
+            .iter()
+            .find(|(flag, _)| flag.load(Ordering::Relaxed))
+            .map(|(_, text)| text.clone());
+        let text = match raised.map(|text| self.clean(Signal::Text(text))) {
+            Some(Signal::Text(text)) => text,
+            _ => return,
+        };
+        self.shut_down = true;
+        self.flush_withheld();
+        self.end_section();
+        self.state.text.clear();
+        let line = format!("{}{}{}\n", self.state.indent, self.state.icon, text);
+        match self.mode {
+            Mode::Ansi => {
+                self.rows += self.height(line.trim_end_matches('\n'));
+                self.write(&line);
+            }
+            Mode::Plain | Mode::Verbose => self.print(&line),
+            Mode::Json => self.print(&format!(
+                "{{\"type\":\"shutdown\",\"text\":\"{}\"}}\n",
+                json_escape(&text)
+            )),
+            Mode::Quiet => {}
+        }
+    }
+
+    // Print a keep-alive line when nothing has been printed for a while
+    fn beat(&mut self) {
+        let interval = match self.heartbeat {
+            Some(interval) if matches!(self.mode, Mode::Plain | Mode::Verbose) => interval,
+            _ => return,
+        };
+        if self.state.text.is_empty()
+            || self.clock.is_paused()
+            || self.last_print.elapsed() < interval
+        {
+            return;
+        }
+        let elapsed = self.locale.0.heartbeat(self.clock.elapsed());
+        let line = format!(
+            "{}{}{} {}\n",
+            self.state.indent, self.state.icon, self.state.text, elapsed
+        );
+        self.print(&line);
+    }
+
+    fn clear(&mut self) {
+        if self.mode == Mode::Ansi {
+            self.write("");
+        }
+    }
+
+    // Clear the current line, or what follows the saved cursor in inline mode, and write the content
+    fn write(&mut self, content: &str) {
+        let clear = match self.inline {
+            Some(_) => "\x1B8\x1B[0K",
+            None => "\x1B[2K\x1B[0G",
+        };
+        self.print(&format!("{}{}", clear, content));
+    }
+
+    // Whether the byte budget of the current second is used up
+    fn over_budget(&mut self) -> bool {
+        let budget = match self.budget {
+            Some(budget) => budget,
+            None => return false,
+        };
+        if self.written.0.elapsed() >= Duration::from_secs(1) {
+            self.written = (Instant::now(), 0);
+        }
+        self.written.1 >= budget
+    }
+
+    fn print(&mut self, content: &str) {
+        let stripped;
+        let content = match self.colors {
+            true => content,
+            false => {
+                stripped = strip_colors(content);
+                &stripped
+            }
+        };
+        self.written.1 += content.len();
+        if let Some((start, file)) = &mut self.recorder {
+            record::write(file, start.elapsed(), content);
+        }
+        self.last_print = Instant::now();
+        let _ = self.output.write(content.as_bytes());
+        let _ = self.output.flush();
+    }
+
+    // Print the percentage when it reaches the next milestone
+    fn milestone(&mut self) {
+        let percent = match self.state.percent() {
+            Some(percent) => percent as u8,
+            None => return,
+        };
+        if self.milestones == 0 || matches!(self.mode, Mode::Ansi | Mode::Quiet) {
+            return;
+        }
+        // A lower percentage is the start of the next transfer
+        if self.milestone.is_some_and(|last| percent < last) {
+            self.milestone = None;
+        }
+        let reached = percent / self.milestones * self.milestones;
+        if reached == 0 || self.milestone.is_some_and(|last| reached <= last) {
+            return;
+        }
+        self.milestone = Some(reached);
+        let content = match self.mode {
+            Mode::Json => format!(
+                "{{\"type\":\"progress\",\"text\":\"{}\",\"percent\":{}}}",
+                json_escape(&self.state.text),
+                reached
+            ),
+            _ => format!(
+                "{}{}{} {}%",
+                self.state.indent, self.state.icon, self.state.text, reached
+            )
+            .trim_start()
+            .to_string(),
+        };
+        self.print(&format!("{}\n", content));
+    }
+
+    // Print a line above the current line
+    fn persist(&mut self, mut line: Line) {
+        self.last_withheld = false;
+        if self.mode == Mode::Ansi {
+            let content = line.render(false, &self.theme, &*self.locale.0);
+            // In inline mode the line starts after the prefix
+            let offset = " ".repeat(self.inline.unwrap_or(0));
+            line.row = self.rows;
+            line.rows = self.height(&format!("{}{}", offset, content));
+            self.rows += line.rows;
+            self.write(&format!("{}\n", content));
+            self.inline = None;
+        } else {
+            self.emit(&line);
+        }
+        self.log(&line);
+        self.history.push(line);
+        if let Some(n) = self.dim_after {
+            self.dim_line(n);
+        }
+        self.draw();
+    }
+
+    // Write a plain copy of a line to the log output
+    fn log(&mut self, line: &Line) {
+        if let Some(tee) = &mut self.tee {
+            let _ = tee
+                .write(format!("{}\n", line.render_plain(&self.theme, &*self.locale.0)).as_bytes());
+            let _ = tee.flush();
+        }
+    }
+
+    // Print a line in the modes without animation
+    fn emit(&mut self, line: &Line) {
+        let content = match self.mode {
+            Mode::Ansi => return,
+            Mode::Plain | Mode::Verbose => line.render_plain(&self.theme, &*self.locale.0),
+            Mode::Quiet => match line.kind {
+                Kind::Status(Status::Fail) | Kind::Detail => {
+                    line.render_plain(&self.theme, &*self.locale.0)
+                }
+                _ => return,
+            },
+            Mode::Json => line.render_json(&*self.locale.0),
+        };
+        self.print(&format!("{}\n", content));
+    }
+
+    // Rewrite the most recent status line, or print a new one if there is none
+    fn amend(&mut self, status: Status, text: String) {
+        let elapsed = self.clock.elapsed().saturating_sub(self.step);
+        if self.last_withheld {
+            return self.amend_withheld(status, text, elapsed);
+        }
+        let line = match self.history.last_mut() {
+            Some(line) => line,
+            None => return self.handle_next(status, text),
+        };
+        let old = match line.kind {
+            Kind::Status(old) => old,
+            _ => return self.handle_next(status, text),
+        };
+        recount(&mut self.group, line, old, status);
+        line.kind = Kind::Status(status);
+        line.text = text;
+        line.duration = line.duration.map(|d| d + elapsed);
+        self.step = self.clock.elapsed();
+        let line = line.clone();
+        self.update_health(status);
+        self.log(&line);
+        if self.mode == Mode::Ansi {
+            self.replace(self.history.len() - 1, false);
+        } else {
+            self.emit(&line);
+        }
+        self.flush_details(status);
+    }
+
+    // Rewrite the last withheld line, printing it now if it is no longer 'info' or 'warn'
+    fn amend_withheld(&mut self, status: Status, text: String, elapsed: Duration) {
+        let mut line = match self.withheld.as_mut().and_then(|withheld| withheld.pop()) {
+            Some(line) => line,
+            None => return self.handle_next(status, text),
+        };
+        if let Kind::Status(old) = line.kind {
+            recount(&mut self.group, &line, old, status);
+        }
+        line.kind = Kind::Status(status);
+        line.text = text;
+        line.duration = line.duration.map(|d| d + elapsed);
+        self.step = self.clock.elapsed();
+        self.update_health(status);
+        match &mut self.withheld {
+            Some(withheld) if matches!(status, Status::Info | Status::Warn) => {
+                withheld.push(line);
+                self.details.clear();
+            }
+            _ => {
+                self.persist(line);
+                self.flush_details(status);
+            }
+        }
+    }
+
+    // Rewrite the line that has just got `n` newer lines below it
+    fn dim_line(&mut self, n: usize) {
+        if self.mode != Mode::Ansi {
+            return;
+        }
+        let i = match self.history.len().checked_sub(n + 1) {
+            Some(i) => i,
+            None => return,
+        };
+        if let Kind::Status(Status::Success) = self.history[i].kind {
+            self.rewrite(i, true);
+        }
+    }
+
+    // Print a line of the history again over its rows
+    fn replace(&mut self, i: usize, dim: bool) {
+        let line = &self.history[i];
+        let content = line.render(dim, &self.theme, &*self.locale.0);
+        let rows = self.height(&content);
+        let up = self.rows - line.row;
+        if line.rows == 0 {
+            // Printed before the switch to ANSI mode, it can't be found on screen
+            return;
+        }
+        if up == line.rows {
+            // Nothing below but the current line, the line can grow or shrink
+            self.history[i].rows = rows;
+            self.rows = self.history[i].row + rows;
+            self.write(&format!("\x1B[{}A\x1B[0G\x1B[0J{}\n", up, content));
+        } else if rows == line.rows {
+            let clear = "\x1B[2K\x1B[1B".repeat(rows);
+            self.print(&format!(
+                "\x1B[{}A\x1B[0G{}\x1B[{}A{}\x1B[{}B\x1B[0G",
+                up,
+                clear,
+                rows,
+                content,
+                up - (rows - 1)
+            ));
+        }
+        // Otherwise it would overwrite the lines below it, the screen is left as it is
+        self.draw();
+    }
+
+    // The terminal rows taken by `content` once printed, lines longer than the width wrap
+    fn height(&self, content: &str) -> usize {
+        let width = self.width().max(1);
+        content
+            .split('\n')
+            .map(|row| columns(row).div_ceil(width).max(1))
+            .sum()
+    }
+
+    // Rewrite a line of the history in place
+    fn rewrite(&mut self, i: usize, dim: bool) {
+        if self.mode == Mode::Ansi {
+            self.replace(i, dim);
+        }
+    }
+
+    // Close the current section with a summary line
+    fn end_section(&mut self) {
+        if let Some(group) = self.group.take() {
+            let line = Line::new(
+                Kind::Summary,
+                group.indent.clone(),
+                group.summary(self.clock.elapsed(), &*self.locale.0),
+            );
+            self.persist(line);
+        }
+    }
+}
+
+// The range of characters of `new` that differ from `old`, without the common prefix and suffix
+fn changed(old: &str, new: &str) -> (usize, usize) {
+    let old = old.chars().collect::<Vec<_>>();
+    let new = new.chars().collect::<Vec<_>>();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    (prefix, new.len() - suffix)
+}
+
+/// When the current line is first rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Defer {
+    /// When the loading is created
+    None,
+    /// On the first tick of the animation or the first text
+    Tick,
+    /// On the first text
+    Text,
+}
+
+struct Group {
+    id: usize,
+    // Clock time when the section started
+    start: Duration,
+    indent: String,
+    counts: [usize; 4],
+}
+
+impl Group {
+    fn summary(&self, now: Duration, locale: &dyn Localizer) -> String {
+        let statuses = [Status::Success, Status::Fail, Status::Warn, Status::Info];
+        let counts = statuses
+            .into_iter()
+            .filter(|status| self.counts[*status as usize] > 0)
+            .map(|status| locale.count(status, self.counts[status as usize]))
+            .collect::<Vec<_>>();
+        let counts = if counts.is_empty() {
+            locale.nothing_done()
+        } else {
+            counts.join(", ")
+        };
+        format!(
+            "{} ({})",
+            counts,
+            HumanDuration::new(now.saturating_sub(self.start))
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Kind {
+    Status(Status),
+    Section,
+    Summary,
+    Detail,
+}
+
+impl Kind {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Kind::Status(Status::Success) => "success",
+            Kind::Status(Status::Fail) => "fail",
+            Kind::Status(Status::Warn) => "warn",
+            Kind::Status(Status::Info) => "info",
+            Kind::Section => "section",
+            Kind::Summary => "summary",
+            Kind::Detail => "detail",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Line {
+    pub(crate) kind: Kind,
+    pub(crate) prefix: String,
+    pub(crate) text: String,
+    pub(crate) duration: Option<Duration>,
+    pub(crate) slow: Option<(Duration, Duration)>,
+    pub(crate) count: usize,
+    // The id of the section the line was counted in
+    pub(crate) group: Option<usize>,
+    // Where the line was printed in ANSI mode, see `Renderer::rows`,
+    // and the rows it took, 0 if it wasn't printed in ANSI mode
+    pub(crate) row: usize,
+    pub(crate) rows: usize,
+}
+
+impl Line {
+    pub(crate) fn new(kind: Kind, prefix: String, text: String) -> Self {
+        Self {
+            kind,
+            prefix,
+            text,
+            duration: None,
+            slow: None,
+            count: 1,
+            group: None,
+            row: 0,
+            rows: 0,
+        }
+    }
+
+    // The text with the number of collapsed duplicates
+    fn label(&self, locale: &dyn Localizer) -> String {
+        match self.count {
+            1 => self.text.clone(),
+            n => format!("{} {}", self.text, locale.repeated(n)),
+        }
+    }
+
+    fn duration_style(&self) -> &'static str {
+        match (self.duration, self.slow) {
+            (Some(d), Some((_, fail))) if d > fail => "\x1B[31m",
+            (Some(d), Some((warn, _))) if d > warn => "\x1B[33m",
+            _ => "\x1B[2m",
+        }
+    }
+
+    fn render(&self, dim: bool, theme: &Theme, locale: &dyn Localizer) -> String {
+        let status = match self.kind {
+            Kind::Status(status) => status,
+            Kind::Section => return format!("{}\x1B[1;4m{}\x1B[0m", self.prefix, self.text),
+            Kind::Summary | Kind::Detail => {
+                return format!("{}\x1B[2m{}\x1B[0m", self.prefix, self.text)
+            }
+        };
+        let duration = match self.duration {
+            Some(d) => format!(" ({})", HumanDuration::new(d)),
+            None => String::new(),
+        };
+        let symbol = format!("{}{}\x1B[0m", theme.color(status), theme.get(status));
+        if dim {
+            format!(
+                "{}{} \x1B[2m{}{}\x1B[0m",
+                self.prefix,
+                symbol,
+                self.label(locale),
+                duration
+            )
+        } else if duration.is_empty() {
+            format!("{}{} {}", self.prefix, symbol, self.label(locale))
+        } else {
+            format!(
+                "{}{} {}{}{}\x1B[0m",
+                self.prefix,
+                symbol,
+                self.label(locale),
+                self.duration_style(),
+                duration
+            )
+        }
+    }
+
+    fn render_plain(&self, theme: &Theme, locale: &dyn Localizer) -> String {
+        let status = match self.kind {
+            Kind::Status(status) => status,
+            Kind::Section | Kind::Summary | Kind::Detail => {
+                return format!("{}{}", self.prefix, self.text)
+            }
+        };
+        match self.duration {
+            Some(d) => format!(
+                "{}{} {} ({})",
+                self.prefix,
+                theme.get(status),
+                self.label(locale),
+                HumanDuration::new(d)
+            ),
+            None => format!(
+                "{}{} {}",
+                self.prefix,
+                theme.get(status),
+                self.label(locale)
+            ),
+        }
+    }
+
+    fn render_json(&self, locale: &dyn Localizer) -> String {
+        let kind = self.kind.name();
+        match self.duration {
+            Some(d) => format!(
+                "{{\"type\":\"{}\",\"text\":\"{}\",\"duration_ms\":{}}}",
+                kind,
+                json_escape(&self.label(locale)),
+                d.as_millis()
+            ),
+            None => format!(
+                "{{\"type\":\"{}\",\"text\":\"{}\"}}",
+                kind,
+                json_escape(&self.label(locale))
+            ),
+        }
+    }
+}
+
+// Move an amended line from the count of its old status to the new one in the current section,
+// lines restored from a transcript or sent by a child process were never counted
+fn recount(group: &mut Option<Group>, line: &Line, old: Status, status: Status) {
+    match group {
+        Some(group) if line.group == Some(group.id) => {
+            let from = &mut group.counts[old as usize];
+            *from = from.saturating_sub(line.count);
+            group.counts[status as usize] += line.count;
+        }
+        _ => {}
+    }
+}
+
+// Remove the color and style sequences such as `\x1B[1;31m`, keeping the cursor movements
+fn strip_colors(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("\x1B[") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let params = after
+            .find(|c: char| !c.is_ascii_digit() && c != ';')
+            .unwrap_or(after.len());
+        match after[params..].starts_with('m') {
+            true => rest = &after[params + 1..],
+            false => {
+                out.push_str("\x1B[");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// Show the control characters as escapes such as `\u{1b}`, except newlines if `newlines` is set
+pub(crate) fn escape_control(text: String, newlines: bool) -> String {
+    let escaped = |c: char| c.is_control() && !(newlines && c == '\n');
+    if !text.contains(escaped) {
+        return text;
+    }
+    text.chars()
+        .map(|c| match escaped(c) {
+            true => c.escape_default().to_string(),
+            false => c.to_string(),
+        })
+        .collect()
+}
+
+pub(crate) fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn renderer() -> Renderer {
+        Renderer::new(Output::Sink, Mode::Plain)
+    }
+
+    // A renderer writing to a buffer
+    fn capture(mode: Mode) -> (Renderer, Arc<std::sync::Mutex<Vec<u8>>>) {
+        #[derive(Clone)]
+        struct Buffer(Arc<std::sync::Mutex<Vec<u8>>>);
+
+        impl Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Buffer(Arc::default());
+        let output = Output::Writer(crate::Writer(Box::new(buffer.clone())));
+        (Renderer::new(output, mode), buffer.0)
+    }
+
+    fn output(buffer: &Arc<std::sync::Mutex<Vec<u8>>>) -> String {
+        String::from_utf8(buffer.lock().unwrap().clone()).unwrap()
+    }
+
+    #[test]
+    fn milestones() {
+        let (mut renderer, buffer) = capture(Mode::Plain);
+        renderer.handle(Signal::Text("Download".into()));
+        for percent in [0, 5, 12, 15, 19, 47, 100, 3, 20] {
+            renderer.handle(Signal::Percent(percent));
+        }
+        assert_eq!(
+            output(&buffer),
+            "Download 10%\nDownload 40%\nDownload 100%\nDownload 20%\n"
+        );
+
+        let (mut renderer, buffer) = capture(Mode::Json);
+        renderer.handle(Signal::Milestones(50));
+        renderer.handle(Signal::Percent(60));
+        assert_eq!(
+            output(&buffer),
+            "{\"type\":\"progress\",\"text\":\"\",\"percent\":50}\n"
+        );
+
+        let (mut renderer, buffer) = capture(Mode::Plain);
+        renderer.handle(Signal::Milestones(0));
+        renderer.handle(Signal::Percent(60));
+        assert_eq!(output(&buffer), "");
+    }
+
+    #[test]
+    fn amend_restored_line_in_section() {
+        let mut renderer = renderer();
+        renderer.handle(Signal::Section("S".into()));
+        renderer.handle(Signal::Restore(Transcript {
+            lines: vec![Line::new(
+                Kind::Status(Status::Success),
+                String::new(),
+                "restored".into(),
+            )],
+        }));
+        renderer.handle(Signal::Amend(Status::Fail, "x".into()));
+        let line = renderer.history.last().unwrap();
+        assert!(matches!(line.kind, Kind::Status(Status::Fail)));
+        assert_eq!(line.text, "x");
+        assert_eq!(renderer.group.as_ref().unwrap().counts, [0; 4]);
+    }
+
+    #[test]
+    fn amend_counted_line_in_section() {
+        let mut renderer = renderer();
+        renderer.handle(Signal::Section("S".into()));
+        renderer.handle(Signal::Next(Status::Warn, "retrying".into()));
+        renderer.handle(Signal::Amend(Status::Success, "ok".into()));
+        let counts = renderer.group.as_ref().unwrap().counts;
+        assert_eq!(counts[Status::Success as usize], 1);
+        assert_eq!(counts[Status::Warn as usize], 0);
+    }
+
+    #[test]
+    fn localized_repeats() {
+        struct Short;
+
+        impl Localizer for Short {
+            fn repeated(&self, n: usize) -> String {
+                format!("x{}", n)
+            }
+        }
+
+        let (mut renderer, buffer) = capture(Mode::Plain);
+        renderer.handle(Signal::Locale(Locale(Box::new(Short))));
+        renderer.handle(Signal::Dedup(5, Dedup::Exact));
+        for _ in 0..3 {
+            renderer.handle(Signal::Next(Status::Warn, "retrying".into()));
+        }
+        let line = renderer.history.last().unwrap();
+        assert!(line
+            .render_plain(&renderer.theme, &Short)
+            .ends_with("retrying x3"));
+        assert!(!output(&buffer).contains('×'));
+    }
+
+    #[test]
+    fn amend_withheld_line() {
+        let (mut renderer, buffer) = capture(Mode::Plain);
+        renderer.handle(Signal::DeferStatus(true));
+        renderer.handle(Signal::Next(Status::Success, "Step 1 done".into()));
+        renderer.handle(Signal::Next(Status::Warn, "retrying".into()));
+        renderer.handle(Signal::Amend(Status::Success, "retry ok".into()));
+        let texts = renderer
+            .history
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, ["Step 1 done", "retry ok"]);
+        assert!(renderer.withheld.as_ref().unwrap().is_empty());
+        assert!(!output(&buffer).contains("retrying"));
+
+        renderer.handle(Signal::Next(Status::Info, "note".into()));
+        renderer.handle(Signal::Amend(Status::Warn, "note!".into()));
+        renderer.handle(Signal::DeferStatus(false));
+        assert_eq!(renderer.history.last().unwrap().text, "note!");
+        assert_eq!(renderer.history.len(), 3);
+    }
+
+    #[test]
+    fn shutdown_flag() {
+        let (mut renderer, buffer) = capture(Mode::Ansi);
+        let flag = Arc::new(AtomicBool::new(false));
+        renderer.handle(Signal::ShutdownFlag(flag.clone(), "Shutting down".into()));
+        renderer.handle(Signal::Text("Working".into()));
+        flag.store(true, Ordering::Relaxed);
+        renderer.handle(Signal::Frame("*".into()));
+        let printed = output(&buffer);
+        assert!(printed.ends_with("\x1B[2K\x1B[0GShutting down\n"));
+        // The line isn't drawn again
+        renderer.handle(Signal::Text("Working".into()));
+        renderer.handle(Signal::Frame("*".into()));
+        assert_eq!(output(&buffer), printed);
+
+        let (mut renderer, buffer) = capture(Mode::Plain);
+        let flag = Arc::new(AtomicBool::new(true));
+        renderer.handle(Signal::ShutdownFlag(flag, "Shutting down".into()));
+        renderer.handle(Signal::Next(Status::Info, "late".into()));
+        assert_eq!(output(&buffer), "Shutting down\nℹ late\n");
+    }
+
+    #[test]
+    fn no_color() {
+        assert_eq!(
+            strip_colors("\x1B[2K\x1B[0G\x1B[32m✔\x1B[0m a \x1B[38;5;130mb\x1B[1A"),
+            "\x1B[2K\x1B[0G✔ a b\x1B[1A"
+        );
+        let (mut renderer, buffer) = capture(Mode::Ansi);
+        renderer.colors = false;
+        renderer.handle(Signal::Next(Status::Success, "Done".into()));
+        assert_eq!(output(&buffer), "\x1B[2K\x1B[0G✔ Done\n\x1B[2K\x1B[0G ");
+    }
+
+    #[test]
+    fn amend_wrapped_line() {
+        let (mut renderer, buffer) = capture(Mode::Ansi);
+        renderer.handle(Signal::Next(Status::Warn, "x".repeat(200)));
+        assert_eq!(renderer.history[0].rows, 3);
+        buffer.lock().unwrap().clear();
+        renderer.handle(Signal::Amend(Status::Success, "ok".into()));
+        assert!(output(&buffer).contains("\x1B[3A\x1B[0G\x1B[0J\x1B[32m✔\x1B[0m ok\n"));
+        assert_eq!(renderer.rows, 1);
+    }
+
+    #[test]
+    fn dim_above_wrapped_lines() {
+        let (mut renderer, buffer) = capture(Mode::Ansi);
+        renderer.handle(Signal::DimAfter(2));
+        renderer.handle(Signal::Next(Status::Success, "a".into()));
+        renderer.handle(Signal::Next(Status::Info, "x".repeat(100)));
+        buffer.lock().unwrap().clear();
+        renderer.handle(Signal::Next(Status::Info, "y".repeat(100)));
+        // 'a' is 5 rows up: 2 rows for each long line and 1 for itself
+        assert!(output(&buffer).contains("\x1B[5A\x1B[0G\x1B[2K\x1B[1B\x1B[1A"));
+        assert!(output(&buffer).contains("\x1B[5B\x1B[0G"));
+
+        let flag = Arc::new(AtomicBool::new(true));
+        renderer.handle(Signal::ShutdownFlag(flag, "Stopping".into()));
+        renderer.handle(Signal::Frame("*".into()));
+        assert_eq!(renderer.rows, 6);
+    }
+
+    #[test]
+    fn transfer() {
+        struct Short;
+
+        impl Localizer for Short {
+            fn time_left(&self, _: Duration) -> String {
+                String::from("soon")
+            }
+        }
+
+        let mut renderer = renderer();
+        renderer.handle(Signal::Locale(Locale(Box::new(Short))));
+        renderer.handle(Signal::Transfer(Some((0, Some(2048)))));
+        renderer.handle(Signal::Transfer(Some((1024, Some(2048)))));
+        assert!(renderer
+            .state
+            .right
+            .starts_with("50% · 1.0 KiB / 2.0 KiB · "));
+        assert!(renderer.state.right.ends_with(" · soon"));
+        assert_eq!(renderer.state.percent(), Some(50));
+        renderer.handle(Signal::Transfer(None));
+        assert_eq!(renderer.state.right, "");
+    }
+
+    #[test]
+    fn inline_fits_after_prefix() {
+        let (mut renderer, buffer) = capture(Mode::Ansi);
+        renderer.handle(Signal::Inline(70));
+        renderer.handle(Signal::Frame("*".into()));
+        renderer.handle(Signal::Text("x".repeat(100)));
+        let printed = output(&buffer);
+        let last = printed.rsplit("\x1B8\x1B[0K").next().unwrap();
+        assert!(columns(last) < 10, "{:?}", last);
+    }
+}
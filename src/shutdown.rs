@@ -0,0 +1,51 @@
+use crate::{Loading, Signal};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A flag that a signal handler can raise to show a prepared text, see [`Loading::shutdown_flag`]
+#[derive(Debug, Clone)]
+pub struct ShutdownFlag(Arc<AtomicBool>);
+
+impl ShutdownFlag {
+    /// Print the prepared text in place of the line and stop drawing the line,
+    /// when the loading handles its next update
+    ///
+    /// Only stores to an atomic, so it is async-signal-safe
+    pub fn raise(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Loading {
+    /// Prepare a text to show when the returned flag is raised, e.g. from a SIGTERM handler
+    /// where nothing can be allocated or locked
+    ///
+    /// Once the text is printed the line is cleared and no longer drawn, the status lines
+    /// that follow are still printed
+    ///
+    /// ```
+    /// use loading::{Loading, ShutdownFlag};
+    /// use std::sync::OnceLock;
+    ///
+    /// static SHUTDOWN: OnceLock<ShutdownFlag> = OnceLock::new();
+    ///
+    /// // Called by the signal handler
+    /// fn on_sigterm() {
+    ///     if let Some(flag) = SHUTDOWN.get() {
+    ///         flag.raise();
+    ///     }
+    /// }
+    ///
+    /// let loading = Loading::default();
+    /// let _ = SHUTDOWN.set(loading.shutdown_flag("Shutting down..."));
+    /// on_sigterm();
+    /// loading.end();
+    /// ```
+    pub fn shutdown_flag<T: ToString>(&self, text: T) -> ShutdownFlag {
+        let flag = Arc::new(AtomicBool::new(false));
+        let _ = self
+            .sender
+            .send(Signal::ShutdownFlag(flag.clone(), text.to_string()));
+        ShutdownFlag(flag)
+    }
+}
@@ -0,0 +1,334 @@
+use crate::render::escape_control;
+use crate::terminal::char_width;
+use crate::Spinner;
+use std::iter::Peekable;
+use std::time::Duration;
+
+/// The spinner frame, the texts, the position and the elapsed time without any I/O,
+/// for frontends that drive their own clock
+///
+/// The terminal [`Loading`](crate::Loading) keeps the content of its line in a `State` too,
+/// but its frames come from the animation thread and its time from a clock that leaves out
+/// the paused intervals
+///
+/// ```
+/// use loading::{Spinner, State};
+/// use std::time::Duration;
+///
+/// let mut spinner = Spinner::new(vec!["-", "+"]);
+/// spinner.interval(Duration::from_millis(100));
+///
+/// let mut state = State::new(spinner);
+/// state.set_text("Loading");
+/// assert_eq!(state.line(), "- Loading");
+///
+/// state.advance(Duration::from_millis(150));
+/// assert_eq!(state.line(), "+ Loading");
+/// assert_eq!(state.elapsed(), Duration::from_millis(150));
+/// ```
+#[derive(Debug, Clone)]
+pub struct State {
+    spinner: Spinner,
+    pub(crate) frame: String,
+    pub(crate) text: String,
+    // The secondary text, aligned to the right
+    pub(crate) right: String,
+    pub(crate) indent: String,
+    // The icon followed by a space, or nothing
+    pub(crate) icon: String,
+    position: Option<(u64, Option<u64>)>,
+    elapsed: Duration,
+    // Time since the current frame was shown
+    lag: Duration,
+}
+
+impl State {
+    pub fn new(mut spinner: Spinner) -> Self {
+        let frame = spinner.next();
+        Self {
+            frame,
+            ..Self::blank(spinner)
+        }
+    }
+
+    // No frame yet, for the terminal renderer which receives its frames
+    pub(crate) fn blank(spinner: Spinner) -> Self {
+        Self {
+            spinner,
+            frame: String::new(),
+            text: String::new(),
+            right: String::new(),
+            indent: String::new(),
+            icon: String::new(),
+            position: None,
+            elapsed: Duration::ZERO,
+            lag: Duration::ZERO,
+        }
+    }
+
+    /// Move the clock forward by `dt`, stepping the frame once per spinner interval
+    pub fn advance(&mut self, dt: Duration) {
+        self.elapsed += dt;
+        self.lag += dt;
+        let interval = self.spinner.interval;
+        while self.lag >= interval {
+            self.lag -= interval;
+            self.frame = self.spinner.next();
+        }
+    }
+
+    pub fn set_text<T: ToString>(&mut self, text: T) {
+        self.text = text.to_string();
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn frame(&self) -> &str {
+        &self.frame
+    }
+
+    /// See [`Loading::right_text`](crate::Loading::right_text)
+    pub fn set_right_text<T: ToString>(&mut self, text: T) {
+        self.right = text.to_string();
+    }
+
+    pub fn right_text(&self) -> &str {
+        &self.right
+    }
+
+    /// See [`Loading::indent`](crate::Loading::indent)
+    pub fn set_indent(&mut self, n: usize) {
+        self.indent = "  ".repeat(n);
+    }
+
+    /// See [`Loading::icon`](crate::Loading::icon)
+    pub fn set_icon<T: ToString>(&mut self, icon: T) {
+        let icon = icon.to_string();
+        self.icon = if icon.is_empty() { icon } else { icon + " " };
+    }
+
+    /// Set the position and, when known, the total
+    pub fn set_position(&mut self, done: u64, total: Option<u64>) {
+        self.position = Some((done, total));
+    }
+
+    pub fn position(&self) -> Option<(u64, Option<u64>)> {
+        self.position
+    }
+
+    /// The position as a percentage of the total, when both are known
+    pub fn percent(&self) -> Option<u64> {
+        match self.position {
+            Some((done, Some(total))) if total > 0 => Some((done * 100 / total).min(100)),
+            _ => None,
+        }
+    }
+
+    /// The sum of all `dt` passed to [`State::advance`]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The indent, the icon, the frame and the text, followed by the right text if any
+    pub fn line(&self) -> String {
+        let line = format!("{}{}{} {}", self.indent, self.icon, self.frame, self.text);
+        match self.right.is_empty() {
+            true => line,
+            false => format!("{} {}", line, self.right),
+        }
+    }
+}
+
+/// The bytes written to redraw the line of `state` on a terminal of `width` columns:
+/// the clear sequence followed by the line as [`Loading`](crate::Loading) draws it,
+/// cut to leave the last column free
+///
+/// Control characters in the texts are escaped, escape sequences in the frame and the icon
+/// are kept whole, and wide characters such as emoji count as two columns
+///
+/// ```
+/// use loading::{render_line, Spinner, State};
+///
+/// let mut state = State::new(Spinner::new(vec!["\x1B[36m-\x1B[0m"]));
+/// state.set_text("Downloading crates");
+///
+/// let line = render_line(&state, 16);
+/// assert_eq!(line, "\x1B[2K\x1B[0G\x1B[36m-\x1B[0m Downloading c\x1B[0m");
+/// ```
+pub fn render_line(state: &State, width: usize) -> String {
+    let prefix = format!("{}{}{} ", state.indent, state.icon, state.frame);
+    let text = escape_control(state.text.clone(), false);
+    let right = escape_control(state.right.clone(), false);
+    format!("\x1B[2K\x1B[0G{}", layout(&prefix, &text, &right, width))
+}
+
+// The prefix and the text followed by the right text aligned to one column before the edge,
+// cut to fit `width` columns
+pub(crate) fn layout(prefix: &str, text: &str, right: &str, width: usize) -> String {
+    let width = width.saturating_sub(1);
+    if right.is_empty() {
+        return cut(&format!("{}{}", prefix, text), width);
+    }
+    let available = width.saturating_sub(columns(prefix) + columns(right) + 1);
+    let text_width = columns(text);
+    let line = if text_width <= available {
+        let padding = " ".repeat(available - text_width + 1);
+        format!("{}{}{}{}", prefix, text, padding, right)
+    } else {
+        format!("{}{} {}", prefix, cut(text, available), right)
+    };
+    cut(&line, width)
+}
+
+// The number of terminal columns, without the escape sequences
+pub(crate) fn columns(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1B' {
+            skip_sequence(&mut chars, &mut String::new());
+            continue;
+        }
+        width += char_width(c);
+    }
+    width
+}
+
+// The start of `s` that fits in `width` columns, keeping the escape sequences whole
+// and resetting the style when something was cut
+fn cut(s: &str, width: usize) -> String {
+    let mut line = String::new();
+    let mut visible = 0;
+    let mut styled = false;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1B' {
+            line.push(c);
+            skip_sequence(&mut chars, &mut line);
+            styled = true;
+            continue;
+        }
+        let w = char_width(c);
+        if visible + w > width {
+            if styled {
+                line.push_str("\x1B[0m");
+            }
+            break;
+        }
+        line.push(c);
+        visible += w;
+    }
+    line
+}
+
+// Move the rest of a `CSI` sequence that started with `ESC` to `out`
+fn skip_sequence<I: Iterator<Item = char>>(chars: &mut Peekable<I>, out: &mut String) {
+    if chars.next_if_eq(&'[').is_some() {
+        out.push('[');
+        for c in chars.by_ref() {
+            out.push(c);
+            if c.is_ascii_alphabetic() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A deterministic xorshift generator, enough to vary the inputs
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+            items[self.next() as usize % items.len()]
+        }
+
+        fn text(&mut self, pieces: &[&str]) -> String {
+            let len = self.next() % 12;
+            (0..len).map(|_| self.pick(pieces)).collect()
+        }
+    }
+
+    const TEXT: [&str; 12] = [
+        "a", "Loading ", "é", "e\u{301}", "下载", "📦", "✅", "\t", "\n", "\x1B[1m", "\x07", " ",
+    ];
+    const FRAMES: [&str; 5] = ["-", "⠋", "🌑", "\x1B[36m●\x1B[0m", "[=  ]"];
+
+    // Check that every escape sequence is a complete `CSI` sequence
+    fn whole_sequences(line: &str) -> bool {
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\x1B' {
+                continue;
+            }
+            if chars.next() != Some('[') {
+                return false;
+            }
+            if !chars.by_ref().any(|c| c.is_ascii_alphabetic()) {
+                return false;
+            }
+        }
+        true
+    }
+
+    #[test]
+    fn render_line_invariants() {
+        let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+        for _ in 0..5000 {
+            let mut state = State::new(Spinner::new(vec![rng.pick(&FRAMES)]));
+            state.set_text(rng.text(&TEXT));
+            if rng.next().is_multiple_of(2) {
+                state.set_right_text(rng.text(&TEXT));
+            }
+            state.set_indent(rng.next() as usize % 3);
+            state.set_icon(rng.pick(&["", "📦", "*"]));
+            let width = rng.next() as usize % 60;
+
+            let line = render_line(&state, width);
+            let content = line
+                .strip_prefix("\x1B[2K\x1B[0G")
+                .unwrap_or_else(|| panic!("no clear sequence: {:?}", line));
+            assert!(
+                columns(content) <= width,
+                "{:?} is wider than {}",
+                line,
+                width
+            );
+            assert!(whole_sequences(content), "split sequence in {:?}", line);
+            let text = content.replace(|c: char| c != '\x1B' && !c.is_control(), "");
+            assert!(
+                text.chars().all(|c| c == '\x1B'),
+                "control character in {:?}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn render_line_fits() {
+        let mut state = State::new(Spinner::new(vec!["-"]));
+        state.set_text("Loading");
+        assert_eq!(render_line(&state, 80), "\x1B[2K\x1B[0G- Loading");
+
+        state.set_text("下载下载");
+        assert_eq!(render_line(&state, 8), "\x1B[2K\x1B[0G- 下载");
+
+        state.set_text("abc");
+        state.set_right_text("1/2");
+        assert_eq!(render_line(&state, 12), "\x1B[2K\x1B[0G- abc   1/2");
+        state.set_text("abcdefghij");
+        assert_eq!(render_line(&state, 12), "\x1B[2K\x1B[0G- abcde 1/2");
+    }
+}
@@ -0,0 +1,70 @@
+use crate::Status;
+use std::fmt::{self, Debug, Formatter};
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// What happened during a loading, passed to [`Loading::on_finish`](crate::Loading::on_finish)
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub(crate) counts: [usize; 4],
+    pub(crate) duration: Duration,
+    pub(crate) status: Option<Status>,
+    pub(crate) fail_code: Option<u8>,
+}
+
+impl Summary {
+    /// Number of 'success' lines
+    pub fn success(&self) -> usize {
+        self.counts[Status::Success as usize]
+    }
+
+    /// Number of 'fail' lines
+    pub fn fail(&self) -> usize {
+        self.counts[Status::Fail as usize]
+    }
+
+    /// Number of 'warn' lines
+    pub fn warn(&self) -> usize {
+        self.counts[Status::Warn as usize]
+    }
+
+    /// Number of 'info' lines
+    pub fn info(&self) -> usize {
+        self.counts[Status::Info as usize]
+    }
+
+    /// The time from the creation of the loading to its end
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The status of the last status line
+    pub fn status(&self) -> Option<Status> {
+        self.status
+    }
+
+    /// `0` without 'fail' lines, otherwise `1` or the code set with
+    /// [`Loading::fail_exit_code`](crate::Loading::fail_exit_code)
+    pub fn exit_code(&self) -> ExitCode {
+        match self.fail() {
+            0 => ExitCode::SUCCESS,
+            _ => ExitCode::from(self.fail_code.unwrap_or(1)),
+        }
+    }
+}
+
+pub(crate) struct Callback(pub(crate) Box<dyn FnOnce(&Summary) + Send>);
+
+impl Debug for Callback {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("Callback")
+    }
+}
+
+pub(crate) struct CycleCallback(pub(crate) Box<dyn FnMut(usize) + Send>);
+
+impl Debug for CycleCallback {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("CycleCallback")
+    }
+}
@@ -0,0 +1,208 @@
+use crate::Recording;
+use std::fmt::Write;
+use std::time::Duration;
+
+// Size of a character cell in pixels
+const CELL_WIDTH: f64 = 8.4;
+const CELL_HEIGHT: f64 = 18.0;
+// How long the last screen is shown before the animation starts again
+const HOLD: Duration = Duration::from_secs(2);
+
+impl Recording {
+    /// Render to an animated SVG image of a terminal of `width` columns and `height` rows
+    pub fn to_svg(&self, width: u16, height: u16) -> String {
+        let mut screen = Screen::default();
+        let mut frames: Vec<(Duration, Vec<String>)> = Vec::new();
+        for (time, output) in &self.chunks {
+            screen.feed(output);
+            let rows = screen.render(height as usize);
+            match frames.last_mut() {
+                Some((_, last)) if *last == rows => {}
+                Some((start, last)) if *start == *time => *last = rows,
+                _ => frames.push((*time, rows)),
+            }
+        }
+        let total = frames.last().map_or(Duration::ZERO, |(t, _)| *t) + HOLD;
+
+        let (w, h) = (
+            width as f64 * CELL_WIDTH + 20.0,
+            height as f64 * CELL_HEIGHT + 20.0,
+        );
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             font-family=\"monospace\" font-size=\"14\">\n\
+             <rect width=\"100%\" height=\"100%\" rx=\"6\" fill=\"#1e1e1e\"/>\n"
+        );
+        for (i, (start, rows)) in frames.iter().enumerate() {
+            let end = frames.get(i + 1).map_or(total, |(t, _)| *t);
+            let _ = writeln!(svg, "<g visibility=\"hidden\">");
+            let _ = writeln!(svg, "{}", visibility(*start, end, total));
+            for (y, row) in rows.iter().enumerate() {
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"10\" y=\"{}\" xml:space=\"preserve\">{}</text>",
+                    10.0 + CELL_HEIGHT * (y as f64 + 0.75),
+                    row
+                );
+            }
+            let _ = writeln!(svg, "</g>");
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+// Show a frame from `start` to `end` in a loop of `total`
+fn visibility(start: Duration, end: Duration, total: Duration) -> String {
+    let total = total.as_secs_f64();
+    let (start, end) = (start.as_secs_f64() / total, end.as_secs_f64() / total);
+    let (values, times) = match (start > 0.0, end < 1.0) {
+        (true, true) => ("hidden;visible;hidden", format!("0;{start:.4};{end:.4}")),
+        (true, false) => ("hidden;visible", format!("0;{start:.4}")),
+        (false, true) => ("visible;hidden", format!("0;{end:.4}")),
+        (false, false) => ("visible", String::from("0")),
+    };
+    format!(
+        "<animate attributeName=\"visibility\" values=\"{values}\" keyTimes=\"{times}\" \
+         dur=\"{total:.3}s\" calcMode=\"discrete\" repeatCount=\"indefinite\"/>"
+    )
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Style {
+    color: Option<&'static str>,
+    bold: bool,
+    dim: bool,
+}
+
+// The few escape sequences written by a loading, applied to a grid of characters
+#[derive(Debug, Default)]
+struct Screen {
+    rows: Vec<Vec<(char, Style)>>,
+    row: usize,
+    col: usize,
+    saved: (usize, usize),
+    style: Style,
+}
+
+impl Screen {
+    fn feed(&mut self, output: &str) {
+        let mut chars = output.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1B' => match chars.next() {
+                    Some('[') => {
+                        let mut params = String::new();
+                        while let Some(c) = chars.next_if(|c| !c.is_ascii_alphabetic()) {
+                            params.push(c);
+                        }
+                        if let Some(action) = chars.next() {
+                            self.csi(&params, action);
+                        }
+                    }
+                    Some('7') => self.saved = (self.row, self.col),
+                    Some('8') => (self.row, self.col) = self.saved,
+                    _ => {}
+                },
+                '\r' => self.col = 0,
+                '\n' => {
+                    self.row += 1;
+                    self.col = 0;
+                }
+                c => self.put(c),
+            }
+        }
+    }
+
+    fn put(&mut self, c: char) {
+        let (col, style) = (self.col, self.style);
+        let row = self.line();
+        if col < row.len() {
+            row[col] = (c, style);
+        } else {
+            row.resize(col, (' ', Style::default()));
+            row.push((c, style));
+        }
+        self.col += 1;
+    }
+
+    fn csi(&mut self, params: &str, action: char) {
+        let n = params.parse::<usize>().unwrap_or(0);
+        match action {
+            'K' if n == 2 => self.line().clear(),
+            'K' => {
+                let col = self.col;
+                self.line().truncate(col);
+            }
+            'G' => self.col = n.saturating_sub(1),
+            'A' => self.row = self.row.saturating_sub(n.max(1)),
+            'B' => self.row += n.max(1),
+            'm' => {
+                for param in params.split(';') {
+                    match param {
+                        "" | "0" => self.style = Style::default(),
+                        "1" => self.style.bold = true,
+                        "2" => self.style.dim = true,
+                        "31" => self.style.color = Some("#f14c4c"),
+                        "32" => self.style.color = Some("#23d18b"),
+                        "33" => self.style.color = Some("#f5f543"),
+                        "34" => self.style.color = Some("#3b8eea"),
+                        "39" => self.style.color = None,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn line(&mut self) -> &mut Vec<(char, Style)> {
+        if self.rows.len() <= self.row {
+            self.rows.resize(self.row + 1, Vec::new());
+        }
+        &mut self.rows[self.row]
+    }
+
+    // The last `height` rows as SVG text content
+    fn render(&self, height: usize) -> Vec<String> {
+        let end = self.rows.len().max(self.row + 1);
+        let start = end.saturating_sub(height);
+        (start..end)
+            .map(|i| match self.rows.get(i) {
+                Some(row) => render_row(row),
+                None => String::new(),
+            })
+            .collect()
+    }
+}
+
+fn render_row(row: &[(char, Style)]) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < row.len() {
+        let style = row[i].1;
+        let mut text = String::new();
+        while let Some((c, _)) = row.get(i).filter(|(_, s)| *s == style) {
+            match c {
+                '&' => text.push_str("&amp;"),
+                '<' => text.push_str("&lt;"),
+                '>' => text.push_str("&gt;"),
+                c => text.push(*c),
+            }
+            i += 1;
+        }
+        let _ = write!(
+            out,
+            "<tspan fill=\"{}\"{}{}>{}</tspan>",
+            style.color.unwrap_or("#d4d4d4"),
+            if style.bold {
+                " font-weight=\"bold\""
+            } else {
+                ""
+            },
+            if style.dim { " opacity=\"0.6\"" } else { "" },
+            text
+        );
+    }
+    out
+}
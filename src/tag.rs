@@ -0,0 +1,64 @@
+use crate::Loading;
+
+/// A handle that prefixes the lines of a loading with a task ID, created by [`Loading::tagged`]
+#[derive(Debug, Clone, Copy)]
+pub struct Tagged<'a, T> {
+    loading: &'a Loading,
+    tag: T,
+}
+
+impl Loading {
+    /// Prefix the lines saved through the returned handle with `[tag]`,
+    /// so completions from many threads remain attributable
+    ///
+    /// Lines are printed in the order of the calls, from any thread
+    ///
+    /// ```
+    /// use loading::Loading;
+    /// use std::thread;
+    ///
+    /// let loading = Loading::default();
+    /// loading.sequence(true);
+    /// thread::scope(|s| {
+    ///     for id in 0..3 {
+    ///         let loading = &loading;
+    ///         s.spawn(move || loading.tagged(id).success("Downloaded"));
+    ///     }
+    /// });
+    /// loading.end();
+    /// ```
+    pub fn tagged<T: std::fmt::Display>(&self, tag: T) -> Tagged<'_, T> {
+        Tagged { loading: self, tag }
+    }
+}
+
+impl<T: std::fmt::Display> Tagged<'_, T> {
+    fn label<S: ToString>(&self, text: S) -> String {
+        format!("[{}] {}", self.tag, text.to_string())
+    }
+
+    /// See [`Loading::text`]
+    pub fn text<S: ToString>(&self, text: S) {
+        self.loading.text(self.label(text));
+    }
+
+    /// See [`Loading::success`]
+    pub fn success<S: ToString>(&self, text: S) {
+        self.loading.success(self.label(text));
+    }
+
+    /// See [`Loading::fail`]
+    pub fn fail<S: ToString>(&self, text: S) {
+        self.loading.fail(self.label(text));
+    }
+
+    /// See [`Loading::warn`]
+    pub fn warn<S: ToString>(&self, text: S) {
+        self.loading.warn(self.label(text));
+    }
+
+    /// See [`Loading::info`]
+    pub fn info<S: ToString>(&self, text: S) {
+        self.loading.info(self.label(text));
+    }
+}
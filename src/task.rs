@@ -0,0 +1,132 @@
+use crate::{Loading, Signal};
+use std::any::Any;
+use std::cell::RefCell;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::Sender;
+use std::sync::Once;
+
+thread_local! {
+    // The loading of the innermost `task` running on this thread
+    static TASK: RefCell<Option<Sender<Signal>>> = const { RefCell::new(None) };
+}
+
+impl Loading {
+    /// Run a closure and save the line as 'success', or as 'fail' with the message if it panics
+    ///
+    /// On a panic the line is cleared before the panic message is printed, and the fail line
+    /// is written before the panic is resumed. The loading is left paused,
+    /// call [`Loading::resume`] to keep using it after catching the panic
+    ///
+    /// ```
+    /// use loading::Loading;
+    ///
+    /// let loading = Loading::default();
+    /// let sum = loading.task("Adding", || 1 + 2);
+    /// assert_eq!(sum, 3);
+    /// loading.end();
+    /// ```
+    pub fn task<T: ToString, R, F: FnOnce() -> R>(&self, text: T, f: F) -> R {
+        let text = text.to_string();
+        self.text(&text);
+
+        hook();
+        let outer = TASK.with(|task| task.replace(Some(self.sender.clone())));
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        TASK.with(|task| *task.borrow_mut() = outer);
+
+        match result {
+            Ok(value) => {
+                self.success(text);
+                value
+            }
+            Err(payload) => {
+                // Keep the line cleared by the panic hook, the fail line doesn't draw it again
+                self.pause();
+                self.fail(format!("{}: {}", text, message(&*payload)));
+                // Wait until the fail line is written before unwinding further
+                let _ = self.draw_into(&mut io::sink());
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+}
+
+// Chain a panic hook that hides the line of the running task before the panic message is printed
+fn hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let sender = TASK.with(|task| task.borrow().clone());
+            if let Some(sender) = sender {
+                let _ = sender.send(Signal::Pause);
+                // Without threads the line is cleared when the task writes the fail line
+                #[cfg(not(feature = "no-threads"))]
+                {
+                    let (done, wait) = std::sync::mpsc::channel();
+                    if sender.send(Signal::Draw(done)).is_ok() {
+                        // Don't hang the panic on a stuck output
+                        let _ = wait.recv_timeout(std::time::Duration::from_secs(1));
+                    }
+                }
+            }
+            previous(info);
+        }));
+    });
+}
+
+fn message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "panicked"
+    }
+}
+
+#[cfg(all(test, not(feature = "no-threads")))]
+mod tests {
+    use super::*;
+    use crate::Spinner;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    #[derive(Clone)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn cleared_before_unwinding() {
+        let buffer = Buffer(Arc::default());
+        let mut spinner = Spinner::new(vec!["*"]);
+        spinner.interval(Duration::from_millis(10));
+        let loading = Loading::with_writer(buffer.clone(), spinner);
+        loading.force_ansi();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            loading.task("Adding", || {
+                thread::sleep(Duration::from_millis(50));
+                panic!("boom")
+            })
+        }));
+        assert!(result.is_err());
+        // A few frames later nothing was drawn after the fail line
+        thread::sleep(Duration::from_millis(50));
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.ends_with("Adding: boom\n"), "{:?}", output);
+        loading.end();
+    }
+}
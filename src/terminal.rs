@@ -0,0 +1,320 @@
+use std::env;
+use std::io::{stderr, stdout, IsTerminal};
+
+/// The width of the terminal, from the `COLUMNS` environment variable if it can't be queried
+#[cfg(unix)]
+pub(crate) fn width(fd: std::os::fd::BorrowedFd) -> Option<usize> {
+    sys::width(fd).or_else(columns)
+}
+
+/// The width of the terminal from the `COLUMNS` environment variable
+#[cfg(not(unix))]
+pub(crate) fn width() -> Option<usize> {
+    columns()
+}
+
+// Terminals that don't support clearing the line, like `TERM=dumb` in some IDE run panels
+pub(crate) fn dumb_terminal() -> bool {
+    env::var("TERM").is_ok_and(|term| term == "dumb")
+}
+
+// `NO_COLOR` set to a non-empty value asks for output without colors, see https://no-color.org
+pub(crate) fn no_color() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+fn columns() -> Option<usize> {
+    env::var("COLUMNS").ok()?.parse().ok()
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::os::fd::{AsRawFd, BorrowedFd};
+    use std::os::raw::{c_int, c_ulong, c_ushort};
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Winsize {
+        ws_row: c_ushort,
+        ws_col: c_ushort,
+        ws_xpixel: c_ushort,
+        ws_ypixel: c_ushort,
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    const TIOCGWINSZ: c_ulong = 0x40087468;
+
+    extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    pub(super) fn width(fd: BorrowedFd) -> Option<usize> {
+        let mut size = Winsize::default();
+        // SAFETY: `TIOCGWINSZ` only writes a `winsize` struct to the pointer
+        let ret = unsafe { ioctl(fd.as_raw_fd(), TIOCGWINSZ, &mut size) };
+        (ret == 0 && size.ws_col > 0).then_some(size.ws_col as usize)
+    }
+}
+
+/// The number of columns a character takes in a terminal: 0 for combining marks and
+/// zero-width characters, 2 for East Asian wide characters and emoji
+pub(crate) fn char_width(c: char) -> usize {
+    let c = c as u32;
+    let zero = [
+        (0x0300, 0x036F),
+        (0x200B, 0x200F),
+        (0x20D0, 0x20FF),
+        (0xFE00, 0xFE0F),
+        (0xFE20, 0xFE2F),
+    ];
+    let wide = [
+        (0x1100, 0x115F),
+        (0x231A, 0x231B),
+        (0x23E9, 0x23EC),
+        (0x23F0, 0x23F0),
+        (0x23F3, 0x23F3),
+        (0x25FD, 0x25FE),
+        (0x2614, 0x2615),
+        (0x2648, 0x2653),
+        (0x267F, 0x267F),
+        (0x2693, 0x2693),
+        (0x26A1, 0x26A1),
+        (0x26AA, 0x26AB),
+        (0x26BD, 0x26BE),
+        (0x26C4, 0x26C5),
+        (0x26CE, 0x26CE),
+        (0x26D4, 0x26D4),
+        (0x26EA, 0x26EA),
+        (0x26F2, 0x26F3),
+        (0x26F5, 0x26F5),
+        (0x26FA, 0x26FA),
+        (0x26FD, 0x26FD),
+        (0x2705, 0x2705),
+        (0x270A, 0x270B),
+        (0x2728, 0x2728),
+        (0x274C, 0x274C),
+        (0x274E, 0x274E),
+        (0x2753, 0x2755),
+        (0x2757, 0x2757),
+        (0x2795, 0x2797),
+        (0x27B0, 0x27B0),
+        (0x27BF, 0x27BF),
+        (0x2B1B, 0x2B1C),
+        (0x2B50, 0x2B50),
+        (0x2B55, 0x2B55),
+        (0x2E80, 0x303E),
+        (0x3041, 0x33FF),
+        (0x3400, 0x4DBF),
+        (0x4E00, 0x9FFF),
+        (0xA000, 0xA4CF),
+        (0xAC00, 0xD7A3),
+        (0xF900, 0xFAFF),
+        (0xFE30, 0xFE4F),
+        (0xFF00, 0xFF60),
+        (0xFFE0, 0xFFE6),
+        (0x1F004, 0x1F004),
+        (0x1F0CF, 0x1F0CF),
+        (0x1F18E, 0x1F18E),
+        (0x1F191, 0x1F19A),
+        (0x1F200, 0x1F64F),
+        (0x1F680, 0x1F6FF),
+        (0x1F7E0, 0x1F7EB),
+        (0x1F90C, 0x1F9FF),
+        (0x1FA70, 0x1FAFF),
+        (0x20000, 0x3FFFD),
+    ];
+    let within = |ranges: &[(u32, u32)]| {
+        ranges
+            .binary_search_by(|&(start, end)| {
+                if end < c {
+                    std::cmp::Ordering::Less
+                } else if start > c {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    };
+    if c < 0x20 || (0x7F..0xA0).contains(&c) || within(&zero) {
+        0
+    } else if within(&wide) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Characters a terminal can display, from the least to the most capable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Charset {
+    /// Only ASCII characters
+    Ascii,
+    /// Unicode symbols such as braille patterns
+    Unicode,
+    /// Unicode symbols and color emoji
+    Emoji,
+}
+
+impl Charset {
+    /// Guess the charset from the locale and the terminal program
+    pub fn detect() -> Self {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+        if cfg!(windows) && env::var_os("WT_SESSION").is_none() {
+            return Charset::Ascii;
+        }
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .map(|name| var(name))
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !cfg!(windows) && !locale.contains("utf-8") && !locale.contains("utf8") {
+            return Charset::Ascii;
+        }
+        let emoji = [
+            "iTerm.app",
+            "Apple_Terminal",
+            "WezTerm",
+            "vscode",
+            "ghostty",
+        ];
+        if emoji.contains(&var("TERM_PROGRAM").as_str())
+            || env::var_os("WT_SESSION").is_some()
+            || env::var_os("KITTY_WINDOW_ID").is_some()
+        {
+            return Charset::Emoji;
+        }
+        Charset::Unicode
+    }
+}
+
+/// The background color of a terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Background {
+    Dark,
+    Light,
+    Unknown,
+}
+
+impl Background {
+    /// Guess the background from `COLORFGBG`, which is set by rxvt, Konsole and some other terminals
+    pub fn detect() -> Self {
+        let value = env::var("COLORFGBG").unwrap_or_default();
+        match value
+            .rsplit(';')
+            .next()
+            .and_then(|bg| bg.parse::<u8>().ok())
+        {
+            Some(7 | 9..=15) => Background::Light,
+            Some(_) => Background::Dark,
+            None => Background::Unknown,
+        }
+    }
+}
+
+/// The number of colors a terminal can display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    /// No colors, e.g. when the output is not a terminal
+    None,
+    /// The 16 basic ANSI colors
+    Ansi16,
+    /// The 256 color palette
+    Ansi256,
+    /// 24-bit RGB colors
+    TrueColor,
+}
+
+/// What the terminal behind stdout or stderr supports, to style the output printed next to a loading
+///
+/// A loading follows the same `NO_COLOR` rule and writes its lines without colors or styles,
+/// the color depth and the hyperlinks are not used by the loading itself
+///
+/// ```
+/// use loading::{Capabilities, ColorDepth};
+///
+/// let caps = Capabilities::stderr();
+/// let error = match caps.color_depth {
+///     ColorDepth::None => "error".to_string(),
+///     _ => "\x1B[31merror\x1B[0m".to_string(),
+/// };
+/// eprintln!("{}: not found", error);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// Whether the output is a terminal
+    pub is_tty: bool,
+    /// The width in columns, if known
+    pub width: Option<usize>,
+    pub color_depth: ColorDepth,
+    pub charset: Charset,
+    /// Whether `OSC 8` hyperlinks are shown as links
+    pub hyperlinks: bool,
+}
+
+impl Capabilities {
+    /// Probe the terminal behind stdout
+    pub fn stdout() -> Self {
+        #[cfg(unix)]
+        let width = width(std::os::fd::AsFd::as_fd(&stdout()));
+        #[cfg(not(unix))]
+        let width = width();
+        Self::probe(stdout().is_terminal(), width)
+    }
+
+    /// Probe the terminal behind stderr
+    pub fn stderr() -> Self {
+        #[cfg(unix)]
+        let width = width(std::os::fd::AsFd::as_fd(&stderr()));
+        #[cfg(not(unix))]
+        let width = width();
+        Self::probe(stderr().is_terminal(), width)
+    }
+
+    fn probe(is_tty: bool, width: Option<usize>) -> Self {
+        let dumb = dumb_terminal();
+        Self {
+            is_tty,
+            width,
+            color_depth: match is_tty && !dumb {
+                true => ColorDepth::detect(),
+                false => ColorDepth::None,
+            },
+            charset: Charset::detect(),
+            hyperlinks: is_tty && !dumb && hyperlinks(),
+        }
+    }
+}
+
+impl ColorDepth {
+    // Guess the depth from `COLORTERM` and `TERM`, assuming the output is a terminal
+    fn detect() -> Self {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+        if no_color() {
+            return ColorDepth::None;
+        }
+        let colorterm = var("COLORTERM");
+        if colorterm == "truecolor" || colorterm == "24bit" || env::var_os("WT_SESSION").is_some() {
+            return ColorDepth::TrueColor;
+        }
+        if var("TERM").contains("256color") {
+            return ColorDepth::Ansi256;
+        }
+        ColorDepth::Ansi16
+    }
+}
+
+// Terminals known to open `OSC 8` hyperlinks
+fn hyperlinks() -> bool {
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    let vte = env::var("VTE_VERSION")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok());
+    ["iTerm.app", "WezTerm", "vscode", "ghostty"].contains(&program.as_str())
+        || env::var_os("WT_SESSION").is_some()
+        || env::var_os("KITTY_WINDOW_ID").is_some()
+        || vte.is_some_and(|version| version >= 5000)
+}
@@ -0,0 +1,201 @@
+//! Writers that behave like slow or stuck terminals, to check that code using a loading
+//! doesn't hang when the output can't keep up
+//!
+//! A loading created with [`Loading::with_writer`] only prints the status lines,
+//! call [`Loading::force_ansi`] so the scenarios also go through the redraws of the animation
+//!
+//! ```
+//! use loading::testing::{self, BlockingWriter};
+//! use loading::{Loading, Spinner};
+//! use std::time::Duration;
+//!
+//! let (writer, release) = BlockingWriter::new();
+//! let loading = Loading::with_writer(writer, Spinner::default());
+//! loading.force_ansi();
+//! testing::burst(&loading, 1000);
+//! loading.success("OK");
+//!
+//! // The writer is stuck, ending gives up instead of hanging
+//! assert!(!loading.end_timeout(Duration::from_millis(100)));
+//! release.release();
+//! ```
+
+use crate::Loading;
+use std::io::{Result, Write};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// A writer that waits `delay` before each write, like a terminal over a slow connection
+#[derive(Debug)]
+pub struct SlowWriter<W> {
+    inner: W,
+    delay: Duration,
+}
+
+impl<W: Write> SlowWriter<W> {
+    /// Wrap `inner`, sleeping `delay` before each write
+    pub fn new(inner: W, delay: Duration) -> Self {
+        Self { inner, delay }
+    }
+
+    /// Get back the wrapped writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for SlowWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        thread::sleep(self.delay);
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A writer that blocks every write until [`Release::release`] is called,
+/// like a terminal whose output is paused
+#[derive(Debug)]
+pub struct BlockingWriter {
+    released: Arc<(Mutex<bool>, Condvar)>,
+}
+
+/// Unblocks the writes of a [`BlockingWriter`]
+#[derive(Debug, Clone)]
+pub struct Release {
+    released: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl BlockingWriter {
+    /// Create a blocked writer and the handle that releases it
+    pub fn new() -> (Self, Release) {
+        let released = Arc::new((Mutex::new(false), Condvar::new()));
+        let release = Release {
+            released: released.clone(),
+        };
+        (Self { released }, release)
+    }
+}
+
+impl Release {
+    /// Let the pending and the following writes through
+    pub fn release(&self) {
+        let (released, condvar) = &*self.released;
+        *released.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        condvar.notify_all();
+    }
+}
+
+impl Write for BlockingWriter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let (released, condvar) = &*self.released;
+        let mut released = released.lock().unwrap_or_else(|e| e.into_inner());
+        while !*released {
+            released = condvar.wait(released).unwrap_or_else(|e| e.into_inner());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Send `updates` text changes as fast as possible
+pub fn burst(loading: &Loading, updates: usize) {
+    for i in 0..updates {
+        loading.text(format!("Update {}", i));
+    }
+}
+
+/// Alternate text changes and status lines for `duration`
+pub fn churn(loading: &Loading, duration: Duration) {
+    let start = std::time::Instant::now();
+    let mut i = 0;
+    while start.elapsed() < duration {
+        loading.text(format!("Step {}", i));
+        if i % 10 == 0 {
+            loading.info(format!("Checkpoint {}", i));
+        }
+        i += 1;
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Change the width of the line every millisecond for `duration`,
+/// like a terminal window being dragged, while the text keeps changing
+pub fn resize_storm(loading: &Loading, duration: Duration) {
+    let start = std::time::Instant::now();
+    let mut i = 0;
+    while start.elapsed() < duration {
+        // From 1 to 200 columns, including widths too narrow for the spinner
+        loading.resize(Some(1 + i * 37 % 200));
+        loading.text(format!("Resize {}", i));
+        i += 1;
+        thread::sleep(Duration::from_millis(1));
+    }
+    loading.resize(None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Spinner;
+
+    #[test]
+    fn slow_writer() {
+        let writer = SlowWriter::new(std::io::sink(), Duration::from_millis(1));
+        let loading = Loading::with_writer(writer, Spinner::default());
+        loading.force_ansi();
+        burst(&loading, 1000);
+        churn(&loading, Duration::from_millis(50));
+        resize_storm(&loading, Duration::from_millis(50));
+        loading.success("OK");
+        assert!(loading.end_timeout(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn blocking_writer() {
+        let (writer, release) = BlockingWriter::new();
+        let loading = Loading::with_writer(writer, Spinner::default());
+        loading.force_ansi();
+        burst(&loading, 1000);
+        resize_storm(&loading, Duration::from_millis(20));
+        loading.success("OK");
+        assert!(!loading.end_timeout(Duration::from_millis(50)));
+        release.release();
+    }
+
+    #[test]
+    fn released_writer() {
+        let (writer, release) = BlockingWriter::new();
+        let loading = Loading::with_writer(writer, Spinner::default());
+        loading.force_ansi();
+        burst(&loading, 100);
+        release.release();
+        loading.success("OK");
+        assert!(loading.end_timeout(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn no_backlog_behind_blocked_writer() {
+        let (writer, release) = BlockingWriter::new();
+        let mut spinner = Spinner::default();
+        spinner.interval(Duration::from_millis(10));
+        let loading = Loading::with_writer(writer, spinner);
+        loading.force_ansi();
+        loading.text("Stuck");
+        let frames = loading.frames.clone();
+        thread::sleep(Duration::from_millis(100));
+        // At most one frame waits behind the blocked write
+        assert!(frames.pending.load(std::sync::atomic::Ordering::Relaxed));
+        assert!(!loading.end_timeout(Duration::from_millis(50)));
+        thread::sleep(Duration::from_millis(100));
+        // The animation thread is gone, only the blocked output thread still holds the frames
+        assert_eq!(Arc::strong_count(&frames), 2);
+        release.release();
+    }
+}
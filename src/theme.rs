@@ -0,0 +1,65 @@
+use crate::{Background, Status};
+
+/// The symbols of the status lines
+///
+/// Start from a built-in theme and override the symbols that should differ:
+///
+/// ```
+/// use loading::{Loading, Status, Theme};
+///
+/// let mut theme = Theme::named("ascii").unwrap();
+/// theme.symbol(Status::Fail, "FAIL");
+///
+/// let loading = Loading::default();
+/// loading.theme(theme);
+/// loading.fail("Connection refused");
+/// loading.end();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    symbols: [String; 4],
+    background: Background,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::new(["✔", "✖", "⚠", "ℹ"])
+    }
+}
+
+impl Theme {
+    fn new(symbols: [&str; 4]) -> Self {
+        Self {
+            symbols: symbols.map(String::from),
+            background: Background::detect(),
+        }
+    }
+
+    /// A built-in theme: `default`, `ascii` or `emoji`
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "ascii" => Some(Self::new(["+", "x", "!", "i"])),
+            "emoji" => Some(Self::new(["✅", "❌", "⚠️", "ℹ️"])),
+            _ => None,
+        }
+    }
+
+    /// Change the symbol of one status, keeping the others
+    pub fn symbol<T: ToString>(&mut self, status: Status, symbol: T) {
+        self.symbols[status as usize] = symbol.to_string();
+    }
+
+    /// Pick the colors for a background, [`Background::detect`] is used by default
+    pub fn background(&mut self, background: Background) {
+        self.background = background;
+    }
+
+    pub(crate) fn color(&self, status: Status) -> &'static str {
+        status.color(self.background)
+    }
+
+    pub(crate) fn get(&self, status: Status) -> &str {
+        &self.symbols[status as usize]
+    }
+}
@@ -0,0 +1,190 @@
+use crate::render::{Kind, Line};
+use crate::Status;
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+use std::time::Duration;
+
+/// The lines saved by a [`Loading`](crate::Loading)
+///
+/// Tools that re-exec themselves can save it before `exec` and resume from it in the new process
+///
+/// ```no_run
+/// use loading::Loading;
+///
+/// let loading = Loading::default();
+/// loading.success("Downloaded update");
+/// loading.transcript().save("/tmp/update.transcript").unwrap();
+/// loading.end();
+///
+/// // In the new process
+/// let loading = Loading::resume_from("/tmp/update.transcript").unwrap();
+/// loading.success("Installed update");
+/// loading.end();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Transcript {
+    pub(crate) lines: Vec<Line>,
+}
+
+impl Transcript {
+    /// Write the transcript to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut content = String::new();
+        for line in &self.lines {
+            let duration = match line.duration {
+                Some(d) => d.as_millis().to_string(),
+                None => String::from("-"),
+            };
+            let slow = match line.slow {
+                Some((warn, fail)) => format!("{},{}", warn.as_millis(), fail.as_millis()),
+                None => String::from("-"),
+            };
+            content.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\n",
+                line.kind.name(),
+                duration,
+                line.count,
+                slow,
+                escape(&line.prefix),
+                escape(&line.text)
+            ));
+        }
+        fs::write(path, content)
+    }
+
+    /// Read a transcript written by [`Transcript::save`]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let mut lines = Vec::new();
+        for row in content.lines() {
+            // Tabs in the prefix and the text are escaped, transcripts saved before
+            // the count and the slow step thresholds were added have four fields
+            let fields = row.split('\t').collect::<Vec<_>>();
+            let (kind, duration, count, slow, prefix, text) = match fields[..] {
+                [a, b, c, d, e, f] => (a, b, c, d, e, f),
+                [a, b, e, f] => (a, b, "1", "-", e, f),
+                _ => return Err(invalid(row)),
+            };
+            let millis = |n: &str| {
+                n.parse()
+                    .map(Duration::from_millis)
+                    .map_err(|_| invalid(row))
+            };
+            let duration = match duration {
+                "-" => None,
+                n => Some(millis(n)?),
+            };
+            let slow = match slow.split_once(',') {
+                Some((warn, fail)) => Some((millis(warn)?, millis(fail)?)),
+                None if slow == "-" => None,
+                None => return Err(invalid(row)),
+            };
+            lines.push(Line {
+                kind: parse_kind(kind).ok_or_else(|| invalid(row))?,
+                prefix: unescape(prefix),
+                text: unescape(text),
+                duration,
+                slow,
+                count: count.parse().map_err(|_| invalid(row))?,
+                group: None,
+                row: 0,
+                rows: 0,
+            });
+        }
+        Ok(Self { lines })
+    }
+}
+
+fn invalid(row: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("invalid transcript line: {:?}", row),
+    )
+}
+
+fn parse_kind(s: &str) -> Option<Kind> {
+    Some(match s {
+        "success" => Kind::Status(Status::Success),
+        "fail" => Kind::Status(Status::Fail),
+        "warn" => Kind::Status(Status::Warn),
+        "info" => Kind::Status(Status::Info),
+        "section" => Kind::Section,
+        "summary" => Kind::Summary,
+        "detail" => Kind::Detail,
+        _ => return None,
+    })
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+pub(crate) fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load() {
+        let mut dedup = Line::new(
+            Kind::Status(Status::Warn),
+            "  ".into(),
+            "tab\there\nnewline \\n backslash".into(),
+        );
+        dedup.count = 3;
+        dedup.duration = Some(Duration::from_millis(1500));
+        dedup.slow = Some((Duration::from_secs(1), Duration::from_secs(5)));
+        let transcript = Transcript {
+            lines: vec![
+                Line::new(Kind::Section, String::new(), "Build".into()),
+                dedup,
+                Line::new(Kind::Detail, "\\".into(), "\\t".into()),
+            ],
+        };
+        let path = std::env::temp_dir().join(format!("loading-{}.transcript", std::process::id()));
+        transcript.save(&path).unwrap();
+        let loaded = Transcript::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.lines.len(), 3);
+        for (a, b) in transcript.lines.iter().zip(&loaded.lines) {
+            assert_eq!(a.kind.name(), b.kind.name());
+            assert_eq!(a.prefix, b.prefix);
+            assert_eq!(a.text, b.text);
+            assert_eq!(a.duration, b.duration);
+            assert_eq!(a.slow, b.slow);
+            assert_eq!(a.count, b.count);
+        }
+    }
+
+    #[test]
+    fn load_four_fields() {
+        let path = std::env::temp_dir().join(format!("loading-{}.old", std::process::id()));
+        fs::write(&path, "success\t1200\t\tDone\n").unwrap();
+        let loaded = Transcript::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.lines[0].text, "Done");
+        assert_eq!(loaded.lines[0].count, 1);
+        assert_eq!(loaded.lines[0].duration, Some(Duration::from_millis(1200)));
+    }
+}
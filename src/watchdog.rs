@@ -0,0 +1,26 @@
+use crate::Signal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::thread;
+
+/// Held by the animation thread, tells the output thread when the animation dies from a panic
+pub(crate) struct Watchdog {
+    healthy: Arc<AtomicBool>,
+    sender: Sender<Signal>,
+}
+
+impl Watchdog {
+    pub(crate) fn new(healthy: Arc<AtomicBool>, sender: Sender<Signal>) -> Self {
+        Self { healthy, sender }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.healthy.store(false, Ordering::Relaxed);
+            let _ = self.sender.send(Signal::Stalled);
+        }
+    }
+}